@@ -0,0 +1,286 @@
+use crate::net::SocketAddr;
+use socket2::SockAddr;
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Why binding or connecting a Unix domain socket failed, independent of the
+/// OS-specific error code.
+///
+/// The same failure shows up as different `io::Error`s on different
+/// platforms: a missing server is `ENOENT` or `ECONNREFUSED` on Unix but
+/// `WSAECONNREFUSED` on Windows, for example. [`Error::classify`] maps them
+/// all to one variant, which carries the path that was used.
+///
+/// Errors this crate detects itself, like a path with a null byte, are
+/// returned as an `io::Error` wrapping an `Error`, so `classify` finds them
+/// without guessing.
+///
+/// # Examples
+///
+/// ```ignore
+/// match UnixStream::connect(path) {
+///     Ok(stream) => handle(stream),
+///     Err(e) => match win_uds::Error::classify(&e, path) {
+///         Some(win_uds::Error::ServerNotRunning { path }) => {
+///             eprintln!("start the daemon first ({})", path.display());
+///         }
+///         _ => eprintln!("{e}"),
+///     },
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The path doesn't fit into `sun_path`.
+    PathTooLong { path: PathBuf },
+    /// The path contains a null byte.
+    PathContainsNul { path: PathBuf },
+    /// Something is already bound to the path.
+    AddrInUse { path: PathBuf },
+    /// No server is listening on the path: the file doesn't exist, or it is
+    /// left over from a server that exited.
+    ServerNotRunning { path: PathBuf },
+    /// The socket file or one of its directories isn't accessible.
+    PermissionDenied { path: PathBuf },
+    /// The OS doesn't support `AF_UNIX` sockets, such as Windows before
+    /// version 10 1803.
+    AfUnixUnsupported { path: PathBuf },
+}
+
+impl Error {
+    /// Classifies an error returned by binding or connecting to `path`.
+    ///
+    /// Returns `None` for errors that don't fall into any variant. Errors
+    /// returned by this crate's `bind` and `connect` functions record the
+    /// address and operation themselves, and those take precedence over
+    /// `path`. For other errors, `NotFound` is classified as
+    /// [`ServerNotRunning`], which is what it means when connecting.
+    ///
+    /// [`ServerNotRunning`]: Error::ServerNotRunning
+    pub fn classify<P: AsRef<Path>>(error: &io::Error, path: P) -> Option<Error> {
+        let mut error = error;
+        let mut path = path.as_ref();
+        let mut op = None;
+        // Errors from `bind` and `connect` know their own path and operation.
+        if let Some(context) = error.get_ref().and_then(|e| e.downcast_ref::<Context>()) {
+            error = &context.source;
+            op = Some(context.op);
+            if let Some(context_path) = &context.path {
+                path = context_path;
+            }
+        }
+        if let Some(error) = error.get_ref().and_then(|e| e.downcast_ref::<Error>()) {
+            return Some(error.clone());
+        }
+        let path = path.to_path_buf();
+        if is_af_unsupported(error) {
+            return Some(Error::AfUnixUnsupported { path });
+        }
+        if is_name_too_long(error) {
+            return Some(Error::PathTooLong { path });
+        }
+        match error.kind() {
+            io::ErrorKind::AddrInUse => Some(Error::AddrInUse { path }),
+            // A missing directory when binding isn't about a server.
+            io::ErrorKind::NotFound if op == Some(Op::Bind) => None,
+            io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => {
+                Some(Error::ServerNotRunning { path })
+            }
+            io::ErrorKind::PermissionDenied => Some(Error::PermissionDenied { path }),
+            _ => None,
+        }
+    }
+
+    /// Returns the OS error code behind `error`, like
+    /// [`io::Error::raw_os_error`], but also for errors this crate wrapped.
+    ///
+    /// Errors from `bind` and `connect` carry the socket address in their
+    /// message, which means the OS error is their inner error and
+    /// `raw_os_error` on them returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let err = UnixStream::connect(path).unwrap_err();
+    /// if win_uds::Error::raw_os_error(&err) == Some(libc::ECONNREFUSED) {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn raw_os_error(error: &io::Error) -> Option<i32> {
+        let mut error = error;
+        loop {
+            if let Some(code) = error.raw_os_error() {
+                return Some(code);
+            }
+            let inner = error.get_ref()?;
+            error = match inner.downcast_ref::<Context>() {
+                Some(context) => &context.source,
+                None => &inner.downcast_ref::<AfUnixUnsupported>()?.0,
+            };
+        }
+    }
+
+    /// Returns the path the failed operation was using.
+    pub fn path(&self) -> &Path {
+        match self {
+            Error::PathTooLong { path }
+            | Error::PathContainsNul { path }
+            | Error::AddrInUse { path }
+            | Error::ServerNotRunning { path }
+            | Error::PermissionDenied { path }
+            | Error::AfUnixUnsupported { path } => path,
+        }
+    }
+
+    /// Returns the `io::ErrorKind` this error is reported as.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::PathTooLong { .. } | Error::PathContainsNul { .. } => {
+                io::ErrorKind::InvalidInput
+            }
+            Error::AddrInUse { .. } => io::ErrorKind::AddrInUse,
+            Error::ServerNotRunning { .. } => io::ErrorKind::ConnectionRefused,
+            Error::PermissionDenied { .. } => io::ErrorKind::PermissionDenied,
+            Error::AfUnixUnsupported { .. } => io::ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// Returns `true` if `e` says the OS has no `AF_UNIX` support.
+pub(crate) fn is_af_unsupported(e: &io::Error) -> bool {
+    #[cfg(unix)]
+    let code = libc::EAFNOSUPPORT;
+    #[cfg(windows)]
+    let code = windows_sys::Win32::Networking::WinSock::WSAEAFNOSUPPORT;
+    e.raw_os_error() == Some(code)
+        || e.get_ref()
+            .is_some_and(|e| e.downcast_ref::<AfUnixUnsupported>().is_some())
+}
+
+/// The OS error from creating a socket, when it means `AF_UNIX` isn't
+/// available.
+#[derive(Debug)]
+struct AfUnixUnsupported(io::Error);
+
+/// Reports `source`, an error from creating a socket, as `Unsupported` if it
+/// means the OS has no `AF_UNIX` support, and returns it unchanged otherwise.
+pub(crate) fn map_af_unsupported(source: io::Error) -> io::Error {
+    if is_af_unsupported(&source) {
+        io::Error::new(io::ErrorKind::Unsupported, AfUnixUnsupported(source))
+    } else {
+        source
+    }
+}
+
+impl fmt::Display for AfUnixUnsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AF_UNIX sockets are not supported on this system")
+    }
+}
+
+impl error::Error for AfUnixUnsupported {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[cfg(unix)]
+fn is_name_too_long(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::ENAMETOOLONG)
+}
+// Windows has no error code of its own for this; `validate_path` catches it.
+#[cfg(windows)]
+fn is_name_too_long(_: &io::Error) -> bool {
+    false
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path().display();
+        match self {
+            Error::PathTooLong { .. } => write!(f, "socket path is too long: {path}"),
+            Error::PathContainsNul { .. } => {
+                write!(f, "socket path contains a null byte: {path}")
+            }
+            Error::AddrInUse { .. } => write!(f, "socket path is already in use: {path}"),
+            Error::ServerNotRunning { .. } => write!(f, "no server is listening on {path}"),
+            Error::PermissionDenied { .. } => {
+                write!(f, "permission denied for socket path {path}")
+            }
+            Error::AfUnixUnsupported { .. } => write!(
+                f,
+                "AF_UNIX sockets are not supported on this system (socket path {path})"
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> io::Error {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+/// The operation a [`Context`] error came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Bind,
+    Connect,
+}
+
+/// An `io::Error` annotated with the operation and address that caused it.
+///
+/// Wrapped into a new `io::Error` of the same kind, so callers matching on
+/// `ErrorKind` don't notice, while the message names the socket.
+#[derive(Debug)]
+struct Context {
+    op: Op,
+    addr: String,
+    path: Option<PathBuf>,
+    source: io::Error,
+}
+
+/// Attaches `op` and `addr` to `source`, keeping its `ErrorKind`.
+pub(crate) fn with_context(source: io::Error, op: Op, addr: &SockAddr) -> io::Error {
+    let addr = SocketAddr::try_from(addr.clone()).ok();
+    let context = Context {
+        op,
+        addr: addr.as_ref().map_or_else(String::new, ToString::to_string),
+        path: addr
+            .as_ref()
+            .and_then(SocketAddr::as_pathname)
+            .map(Path::to_path_buf),
+        source,
+    };
+    io::Error::new(context.source.kind(), context)
+}
+
+/// Attaches `op` and `path` to `source`, keeping its `ErrorKind`.
+pub(crate) fn with_path_context(source: io::Error, op: Op, path: &Path) -> io::Error {
+    let context = Context {
+        op,
+        addr: path.display().to_string(),
+        path: Some(path.to_path_buf()),
+        source,
+    };
+    io::Error::new(context.source.kind(), context)
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op {
+            Op::Bind => write!(f, "failed to bind {}: {}", self.addr, self.source),
+            Op::Connect => write!(f, "failed to connect to {}: {}", self.addr, self.source),
+        }
+    }
+}
+
+impl error::Error for Context {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
@@ -1,3 +1,7 @@
-#![cfg(windows)]
+#![cfg(any(unix, windows))]
 
+mod error;
 pub mod net;
+
+pub use error::Error;
+pub use net::is_supported;
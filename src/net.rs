@@ -1,30 +1,88 @@
+mod addr;
+mod builder;
+mod flags;
 mod listener;
+mod local;
+mod retry;
 mod stream;
+mod sys;
+mod ucred;
+pub use addr::SocketAddr;
+pub use builder::UnixListenerBuilder;
+pub use flags::MsgFlags;
 pub use listener::*;
+pub use local::{LocalListener, LocalStream};
+pub use retry::RetryPolicy;
 pub use socket2::SockAddr;
 use socket2::Socket;
 pub use stream::*;
+pub use ucred::UCred;
 
 #[cfg(feature = "async")]
 mod async_uds;
 #[cfg(feature = "async")]
 pub use async_uds::*;
 
+use crate::Error;
+use crate::error::is_af_unsupported;
 use std::ffi::OsStr;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
-use std::{io, path::Path};
+use std::{io, path::Path, sync::OnceLock};
 
-/// Validates that a path doesn't contain null bytes.
-/// Returns an error if null bytes are found, matching Unix behavior.
+/// Returns `true` if the OS supports `AF_UNIX` sockets.
+///
+/// Windows gained them in Windows 10 version 1803, and some locked-down
+/// systems disable them. This creates a socket the first time it is called
+/// and caches the answer. Only a failure that means the address family is
+/// missing counts; a socket that can't be created for other reasons, such as
+/// running out of descriptors, doesn't make this return `false`.
+///
+/// When this returns `false`, constructors such as [`UnixStream::connect`]
+/// fail with `ErrorKind::Unsupported`.
+///
+/// # Examples
+///
+/// ```ignore
+/// if !win_uds::is_supported() {
+///     eprintln!("AF_UNIX is unavailable, falling back to TCP");
+/// }
+/// ```
+pub fn is_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| match sys::unix_socket() {
+        Ok(_) => true,
+        Err(e) => !is_af_unsupported(&e),
+    })
+}
+
+/// Validates that a path doesn't contain null bytes and fits into `sun_path`.
+/// Returns an error wrapping [`Error`] otherwise, matching Unix behavior.
 fn validate_path<P: AsRef<Path>>(path: P) -> io::Result<()> {
-    let os_str: &OsStr = path.as_ref().as_os_str();
-    for wchar in os_str.encode_wide() {
-        if wchar == 0 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "paths must not contain null bytes",
-            ));
+    let path = path.as_ref();
+    let os_str: &OsStr = path.as_os_str();
+    #[cfg(unix)]
+    let (has_nul, len) = (os_str.as_bytes().contains(&0), os_str.len());
+    // `sun_path` holds UTF-8 on Windows; other paths are rejected by
+    // `SockAddr::unix`.
+    #[cfg(windows)]
+    let (has_nul, len) = (
+        os_str.encode_wide().any(|wchar| wchar == 0),
+        os_str.to_str().map_or(0, str::len),
+    );
+    if has_nul {
+        return Err(Error::PathContainsNul {
+            path: path.to_path_buf(),
+        }
+        .into());
+    }
+    if len >= sys::SUN_PATH_LEN {
+        return Err(Error::PathTooLong {
+            path: path.to_path_buf(),
         }
+        .into());
     }
     Ok(())
 }
@@ -0,0 +1,217 @@
+use crate::net::sys::{ABSTRACT_NAMESPACE, AF_UNIX, SUN_PATH_LEN, SUN_PATH_OFFSET, sockaddr_un};
+use crate::net::{SockAddr, validate_path};
+use socket2::{SockAddrStorage, socklen_t};
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+use std::{fmt, io, path::Path, slice};
+
+/// An address associated with a Unix domain socket.
+///
+/// Unlike [`SockAddr`], this type is always in the `AF_UNIX` family and knows
+/// how to decode the `sun_path` it carries.
+///
+/// # Examples
+///
+/// ```ignore
+/// let listener = UnixListener::bind("/tmp/sock").unwrap();
+/// let (_stream, addr) = listener.accept().unwrap();
+/// println!("peer: {:?}", addr);
+/// ```
+#[derive(Clone)]
+pub struct SocketAddr(SockAddr);
+
+impl SocketAddr {
+    /// Constructs a `SocketAddr` with the family `AF_UNIX` and the provided path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is longer than `SUN_LEN` or if it contains
+    /// null bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let addr = SocketAddr::from_pathname("/path/to/socket").unwrap();
+    /// assert_eq!(addr.as_pathname(), Some(Path::new("/path/to/socket")));
+    /// ```
+    pub fn from_pathname<P: AsRef<Path>>(path: P) -> io::Result<SocketAddr> {
+        validate_path(&path)?;
+        SockAddr::unix(path).map(SocketAddr)
+    }
+
+    /// Constructs a `SocketAddr` in the abstract namespace.
+    ///
+    /// Abstract addresses start with a null byte and never appear in the
+    /// filesystem, so there is no socket file to clean up afterwards. The name
+    /// may contain arbitrary bytes, including null bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is longer than `SUN_LEN - 1`, or with
+    /// `ErrorKind::Unsupported` on platforms without abstract addresses
+    /// (everything except Linux, Android and Windows).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let addr = SocketAddr::from_abstract_name(b"hidden").unwrap();
+    /// assert_eq!(addr.as_abstract_name(), Some(&b"hidden"[..]));
+    /// ```
+    pub fn from_abstract_name<N: AsRef<[u8]>>(name: N) -> io::Result<SocketAddr> {
+        let name = name.as_ref();
+        if !ABSTRACT_NAMESPACE {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract socket addresses are not supported on this platform",
+            ));
+        }
+        if name.len() >= SUN_PATH_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "abstract name must be shorter than SUN_LEN",
+            ));
+        }
+
+        let mut storage = SockAddrStorage::zeroed();
+        // SAFETY: `sockaddr_un` is one of the `sockaddr_*` types of this platform.
+        let sun = unsafe { storage.view_as::<sockaddr_un>() };
+        sun.sun_family = AF_UNIX as _;
+        // `sun_path[0]` is already zero, which marks the address as abstract.
+        for (dst, &src) in sun.sun_path[1..].iter_mut().zip(name) {
+            *dst = src as _;
+        }
+        let len = SUN_PATH_OFFSET + 1 + name.len();
+        // SAFETY: the storage is initialised up to `len` bytes.
+        Ok(SocketAddr(unsafe {
+            SockAddr::new(storage, len as socklen_t)
+        }))
+    }
+
+    /// Wraps a `SockAddr` returned by the OS for an `AF_UNIX` socket.
+    ///
+    /// Some platforms report unnamed addresses without setting the family,
+    /// so the family is not checked here.
+    pub(crate) fn new(addr: SockAddr) -> SocketAddr {
+        SocketAddr(addr)
+    }
+
+    /// Returns `true` if the address is unnamed.
+    ///
+    /// Sockets that were never bound, such as the client side of a connection,
+    /// have unnamed addresses.
+    pub fn is_unnamed(&self) -> bool {
+        self.sun_path().first().is_none_or(|&b| b == 0) && self.as_abstract_name().is_none()
+    }
+
+    /// Returns the contents of this address if it is a `pathname` address.
+    pub fn as_pathname(&self) -> Option<&Path> {
+        let sun_path = self.sun_path();
+        if sun_path.first().is_none_or(|&b| b == 0) {
+            return None;
+        }
+        // The path is null terminated, but some platforms include the
+        // terminator (or the whole zeroed buffer) in the address length.
+        let len = sun_path
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(sun_path.len());
+        bytes_to_path(&sun_path[..len])
+    }
+
+    /// Returns the contents of this address if it is in the abstract namespace.
+    ///
+    /// Always returns `None` on platforms without abstract addresses.
+    pub fn as_abstract_name(&self) -> Option<&[u8]> {
+        if !ABSTRACT_NAMESPACE {
+            return None;
+        }
+        match self.sun_path().split_first() {
+            // Windows reports unnamed addresses as a zeroed `sun_path` rather
+            // than a truncated length, so an all-zero name means unnamed there.
+            Some((0, name))
+                if !name.is_empty() && (cfg!(not(windows)) || name.iter().any(|&b| b != 0)) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the `sun_path` bytes covered by the address length.
+    fn sun_path(&self) -> &[u8] {
+        let len = (self.0.len() as usize)
+            .saturating_sub(SUN_PATH_OFFSET)
+            .min(SUN_PATH_LEN);
+        // SAFETY: the storage behind `SockAddr` is a `sockaddr_storage`, which
+        // is large enough to hold a `sockaddr_un`, and `len` never reaches
+        // past the end of `sun_path`.
+        unsafe { slice::from_raw_parts(self.0.as_ptr().cast::<u8>().add(SUN_PATH_OFFSET), len) }
+    }
+}
+
+#[cfg(unix)]
+fn bytes_to_path(bytes: &[u8]) -> Option<&Path> {
+    Some(Path::new(std::ffi::OsStr::from_bytes(bytes)))
+}
+
+// Windows stores `sun_path` as UTF-8, see `SockAddr::unix`.
+#[cfg(windows)]
+fn bytes_to_path(bytes: &[u8]) -> Option<&Path> {
+    std::str::from_utf8(bytes).ok().map(Path::new)
+}
+
+impl fmt::Debug for SocketAddr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.as_pathname() {
+            write!(fmt, "{path:?} (pathname)")
+        } else if let Some(name) = self.as_abstract_name() {
+            write!(fmt, "\"{}\" (abstract)", name.escape_ascii())
+        } else if self.is_unnamed() {
+            write!(fmt, "(unnamed)")
+        } else {
+            write!(fmt, "\"{}\" (undecodable)", self.sun_path().escape_ascii())
+        }
+    }
+}
+
+impl fmt::Display for SocketAddr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.as_pathname() {
+            write!(fmt, "{}", path.display())
+        } else if let Some(name) = self.as_abstract_name() {
+            write!(fmt, "@{}", name.escape_ascii())
+        } else if self.is_unnamed() {
+            write!(fmt, "(unnamed)")
+        } else {
+            write!(fmt, "{}", self.sun_path().escape_ascii())
+        }
+    }
+}
+
+impl From<SocketAddr> for SockAddr {
+    fn from(addr: SocketAddr) -> SockAddr {
+        addr.0
+    }
+}
+
+impl AsRef<SockAddr> for SocketAddr {
+    fn as_ref(&self) -> &SockAddr {
+        &self.0
+    }
+}
+
+impl TryFrom<SockAddr> for SocketAddr {
+    type Error = io::Error;
+
+    /// Fails with `InvalidInput` if the address is not in the `AF_UNIX` family.
+    fn try_from(addr: SockAddr) -> io::Result<SocketAddr> {
+        if addr.is_unix() {
+            Ok(SocketAddr(addr))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "address is not in the AF_UNIX family",
+            ))
+        }
+    }
+}
@@ -1,17 +1,31 @@
-//! Async Unix domain socket types using async-io for Windows.
+//! Async Unix domain socket types using async-io.
 //!
 //! These types use `futures_io::{AsyncRead, AsyncWrite}` traits for runtime-agnostic async I/O.
 //! Use `tokio_util::compat` to adapt these types for tokio.
 
-use crate::net::{UnixListener, UnixStream};
-use async_io::Async;
+use crate::error::{Op, with_context};
+use crate::net::listener::{RECLAIM_PROBE_TIMEOUT, reclaim};
+use crate::net::retry::Attempts;
+use crate::net::{
+    MAX_RETRY_DELAY, MsgFlags, RetryPolicy, SocketAddr, UCred, UnixListener, UnixStream,
+    validate_path,
+};
+use async_io::{Async, Timer};
+use futures_core::Stream;
 use futures_io::{AsyncRead, AsyncWrite};
 use socket2::SockAddr;
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, AsSocket, BorrowedSocket, OwnedSocket, RawSocket};
 use std::{
+    future::{Future, poll_fn},
     io,
+    net::Shutdown,
     path::Path,
-    pin::Pin,
-    task::{Context, Poll},
+    pin::{Pin, pin},
+    task::{Context, Poll, ready},
+    time::Duration,
 };
 
 /// Async Unix domain socket stream.
@@ -22,17 +36,114 @@ pub struct AsyncStream(Async<UnixStream>);
 
 impl AsyncStream {
     /// Connects to a Unix domain socket at the given path.
+    ///
+    /// The connect never blocks the executor thread: the socket is created in
+    /// non-blocking mode and the task waits for it to become writable. If the
+    /// listener's backlog is full the attempt is repeated after a short delay.
+    /// Dropping the future cancels the connect.
     pub async fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        let stream = UnixStream::connect(path)?;
-        let async_stream = Async::new(stream)?;
-        Ok(Self(async_stream))
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        Self::connect_addr(&addr).await
+    }
+
+    /// Connects to a Unix domain socket at the given path, giving up after
+    /// `timeout`.
+    ///
+    /// Fails with `ErrorKind::TimedOut` if the connection isn't established
+    /// in time, and with `ErrorKind::InvalidInput` if `timeout` is zero.
+    pub async fn connect_timeout<P: AsRef<Path>>(path: P, timeout: Duration) -> io::Result<Self> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        Self::connect_addr_timeout(&addr, timeout).await
+    }
+
+    /// Connects to a Unix domain socket at the given address, giving up after
+    /// `timeout`.
+    pub async fn connect_addr_timeout(
+        socket_addr: &SockAddr,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        if timeout.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot set a 0 duration timeout",
+            ));
+        }
+        let mut connect = pin!(Self::connect_addr(socket_addr));
+        let mut timer = Timer::after(timeout);
+        poll_fn(|cx| {
+            if let Poll::Ready(res) = connect.as_mut().poll(cx) {
+                return Poll::Ready(res);
+            }
+            ready!(Pin::new(&mut timer).poll(cx));
+            let e = io::ErrorKind::TimedOut.into();
+            Poll::Ready(Err(with_context(e, Op::Connect, socket_addr)))
+        })
+        .await
+    }
+
+    /// Connects to a Unix domain socket at the given path, waiting for the
+    /// server to come up.
+    ///
+    /// See [`UnixStream::connect_with_retry`]; the pauses between attempts
+    /// don't block the executor.
+    pub async fn connect_with_retry<P: AsRef<Path>>(
+        path: P,
+        policy: RetryPolicy,
+    ) -> io::Result<Self> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        let mut attempts = Attempts::new(policy);
+        loop {
+            match Self::connect_addr_timeout(&addr, attempts.remaining()).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => {
+                    Timer::after(attempts.on_error(e)?).await;
+                }
+            }
+        }
+    }
+
+    /// Connects to a Unix domain socket bound to `name` in the abstract namespace.
+    pub async fn connect_abstract<N: AsRef<[u8]>>(name: N) -> io::Result<Self> {
+        let addr = SocketAddr::from_abstract_name(name)?;
+        Self::connect_addr(addr.as_ref()).await
     }
 
     /// Connects to a Unix domain socket at the given address.
+    ///
+    /// See [`connect`](AsyncStream::connect) for how the connect proceeds.
     pub async fn connect_addr(socket_addr: &SockAddr) -> io::Result<Self> {
-        let stream = UnixStream::connect_addr(socket_addr)?;
-        let async_stream = Async::new(stream)?;
-        Ok(Self(async_stream))
+        Self::connect_socket(socket_addr)
+            .await
+            .map_err(|e| with_context(e, Op::Connect, socket_addr))
+    }
+
+    async fn connect_socket(socket_addr: &SockAddr) -> io::Result<Self> {
+        let mut delay = Duration::from_millis(1);
+        let stream = loop {
+            match UnixStream::start_connect(socket_addr) {
+                Ok(stream) => break Async::new_nonblocking(stream)?,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    Timer::after(delay).await;
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        while !stream.get_ref().finish_connect()? {
+            stream.writable().await?;
+        }
+        Ok(Self(stream))
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    ///
+    /// See [`UnixStream::pair`] for how the pair is created on each platform.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = UnixStream::pair()?;
+        Ok((Self(Async::new(a)?), Self(Async::new(b)?)))
     }
 
     /// Creates a new independently owned handle to the underlying socket.
@@ -42,10 +153,83 @@ impl AsyncStream {
         Ok(Self(async_stream))
     }
 
+    /// Wraps a blocking stream, registering it with the reactor.
+    ///
+    /// The stream is put into non-blocking mode.
+    pub fn from_std(stream: UnixStream) -> io::Result<Self> {
+        Ok(Self(Async::new(stream)?))
+    }
+
+    /// Deregisters the stream from the reactor and returns it.
+    ///
+    /// The stream is left in non-blocking mode; call `set_nonblocking(false)`
+    /// before using it for blocking I/O.
+    pub fn into_std(self) -> io::Result<UnixStream> {
+        self.0.into_inner()
+    }
+
     /// Returns a reference to the inner stream.
     pub fn get_ref(&self) -> &UnixStream {
         self.0.get_ref()
     }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.get_ref().local_addr()
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.get_ref().peer_addr()
+    }
+
+    /// Gets the credentials of the process on the other end of this
+    /// connection.
+    ///
+    /// See [`UCred`] for which fields each platform fills in.
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        self.0.get_ref().peer_cred()
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// See [`UnixStream::shutdown`].
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.0.get_ref().shutdown(how)
+    }
+
+    /// Shuts down the write half of this connection, so the peer reads EOF
+    /// after the data sent so far, while this side can keep reading.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// stream.write_all(b"request").await?;
+    /// stream.shutdown_write().await?;
+    /// let mut response = Vec::new();
+    /// stream.read_to_end(&mut response).await?;
+    /// ```
+    pub async fn shutdown_write(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+
+    /// Receives data without removing it from the queue.
+    ///
+    /// Waits until data is available. See [`UnixStream::peek`].
+    pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read_with(|s| s.peek(buf)).await
+    }
+
+    /// Receives data with the given `MSG_*` flags once data is available.
+    ///
+    /// The socket is in non-blocking mode, which [`MsgFlags::WAIT_ALL`]
+    /// doesn't work with, so that flag is ignored and fewer bytes than
+    /// requested may be returned.
+    pub async fn recv_with_flags(&self, buf: &mut [u8], flags: MsgFlags) -> io::Result<usize> {
+        // Windows fails non-blocking receives with `MSG_WAITALL` outright.
+        let flags = flags.without(MsgFlags::WAIT_ALL);
+        self.0.read_with(|s| s.recv_with_flags(buf, flags)).await
+    }
 }
 
 impl AsyncRead for AsyncStream {
@@ -56,6 +240,14 @@ impl AsyncRead for AsyncStream {
     ) -> Poll<io::Result<usize>> {
         Pin::new(&mut self.0).poll_read(cx, buf)
     }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_read_vectored(cx, bufs)
+    }
 }
 
 impl AsyncWrite for AsyncStream {
@@ -67,6 +259,14 @@ impl AsyncWrite for AsyncStream {
         Pin::new(&mut self.0).poll_write(cx, buf)
     }
 
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write_vectored(cx, bufs)
+    }
+
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         Pin::new(&mut self.0).poll_flush(cx)
     }
@@ -76,10 +276,69 @@ impl AsyncWrite for AsyncStream {
     }
 }
 
+impl AsyncRead for &AsyncStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.0).poll_read(cx, buf)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.0).poll_read_vectored(cx, bufs)
+    }
+}
+
+impl AsyncWrite for &AsyncStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.0).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.0).poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &self.0).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &self.0).poll_close(cx)
+    }
+}
+
 /// Async Unix domain socket listener.
 pub struct AsyncListener(Async<UnixListener>);
 
 impl AsyncListener {
+    /// Wraps a blocking listener, registering it with the reactor.
+    ///
+    /// The listener is put into non-blocking mode.
+    pub fn from_std(listener: UnixListener) -> io::Result<Self> {
+        Ok(Self(Async::new(listener)?))
+    }
+
+    /// Deregisters the listener from the reactor and returns it.
+    ///
+    /// The listener is left in non-blocking mode; call
+    /// `set_nonblocking(false)` to make `accept` block again.
+    pub fn into_std(self) -> io::Result<UnixListener> {
+        self.0.into_inner()
+    }
+
     /// Creates a new listener bound to the given path.
     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
         let listener = UnixListener::bind(path)?;
@@ -87,6 +346,24 @@ impl AsyncListener {
         Ok(Self(async_listener))
     }
 
+    /// Creates a new listener bound to the given path, taking over the socket
+    /// file of a server that is no longer running.
+    ///
+    /// See [`UnixListener::bind_reclaim`].
+    pub async fn bind_reclaim<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let probe = AsyncStream::connect_timeout(path, RECLAIM_PROBE_TIMEOUT).await;
+        reclaim(path, probe.map(drop))?;
+        Self::bind(path)
+    }
+
+    /// Creates a new listener bound to `name` in the abstract namespace.
+    pub fn bind_abstract<N: AsRef<[u8]>>(name: N) -> io::Result<Self> {
+        let listener = UnixListener::bind_abstract(name)?;
+        let async_listener = Async::new(listener)?;
+        Ok(Self(async_listener))
+    }
+
     /// Creates a new listener bound to the given address.
     pub fn bind_addr(socket_addr: &SockAddr) -> io::Result<Self> {
         let listener = UnixListener::bind_addr(socket_addr)?;
@@ -95,21 +372,49 @@ impl AsyncListener {
     }
 
     /// Accepts a new incoming connection.
-    pub async fn accept(&self) -> io::Result<(AsyncStream, SockAddr)> {
+    pub async fn accept(&self) -> io::Result<(AsyncStream, SocketAddr)> {
+        poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    /// Polls for a new incoming connection.
+    ///
+    /// Returns `Poll::Pending` and arranges for the current task to be woken
+    /// once the listener becomes readable if no connection is pending yet.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(AsyncStream, SocketAddr)>> {
         loop {
             match self.0.get_ref().accept() {
                 Ok((stream, addr)) => {
                     let async_stream = Async::new(stream)?;
-                    return Ok((AsyncStream(async_stream), addr));
+                    return Poll::Ready(Ok((AsyncStream(async_stream), addr)));
                 }
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    self.0.readable().await?;
+                    ready!(self.0.poll_readable(cx))?;
                 }
-                Err(e) => return Err(e),
+                Err(e) => return Poll::Ready(Err(e)),
             }
         }
     }
 
+    /// Returns a stream of incoming connections.
+    ///
+    /// The stream never ends; errors from `accept` are yielded as they occur.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = AsyncListener::bind("/path/to/the/socket")?;
+    ///
+    /// listener
+    ///     .incoming()
+    ///     .for_each_concurrent(None, |stream| async move {
+    ///         handle_client(stream.unwrap()).await;
+    ///     })
+    ///     .await;
+    /// ```
+    pub fn incoming(&self) -> AsyncIncoming<'_> {
+        AsyncIncoming { listener: self }
+    }
+
     /// Creates a new independently owned handle to the underlying listener.
     pub fn try_clone(&self) -> io::Result<Self> {
         let cloned = self.0.get_ref().try_clone()?;
@@ -121,4 +426,166 @@ impl AsyncListener {
     pub fn get_ref(&self) -> &UnixListener {
         self.0.get_ref()
     }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.get_ref().local_addr()
+    }
+
+    /// Sets whether the socket file is removed when this listener is dropped.
+    ///
+    /// See [`UnixListener::set_unlink_on_drop`].
+    pub fn set_unlink_on_drop(&self, unlink: bool) {
+        self.0.get_ref().set_unlink_on_drop(unlink)
+    }
+}
+
+/// A stream of incoming connections to an [`AsyncListener`].
+///
+/// It never ends. This `struct` is created by [`AsyncListener::incoming`].
+pub struct AsyncIncoming<'a> {
+    listener: &'a AsyncListener,
+}
+
+impl Stream for AsyncIncoming<'_> {
+    type Item = io::Result<AsyncStream>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (stream, _addr) = ready!(self.listener.poll_accept(cx))?;
+        Poll::Ready(Some(Ok(stream)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl TryFrom<UnixStream> for AsyncStream {
+    type Error = io::Error;
+
+    fn try_from(stream: UnixStream) -> io::Result<Self> {
+        Self::from_std(stream)
+    }
+}
+
+impl TryFrom<UnixListener> for AsyncListener {
+    type Error = io::Error;
+
+    fn try_from(listener: UnixListener) -> io::Result<Self> {
+        Self::from_std(listener)
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for AsyncStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+#[cfg(unix)]
+impl AsRawFd for AsyncStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl TryFrom<OwnedFd> for AsyncStream {
+    type Error = io::Error;
+
+    fn try_from(fd: OwnedFd) -> io::Result<Self> {
+        Self::from_std(UnixStream::from(fd))
+    }
+}
+#[cfg(unix)]
+impl TryFrom<AsyncStream> for OwnedFd {
+    type Error = io::Error;
+
+    fn try_from(stream: AsyncStream) -> io::Result<Self> {
+        stream.into_std().map(OwnedFd::from)
+    }
+}
+#[cfg(unix)]
+impl AsFd for AsyncListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+#[cfg(unix)]
+impl AsRawFd for AsyncListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl TryFrom<OwnedFd> for AsyncListener {
+    type Error = io::Error;
+
+    fn try_from(fd: OwnedFd) -> io::Result<Self> {
+        Self::from_std(UnixListener::from(fd))
+    }
+}
+#[cfg(unix)]
+impl TryFrom<AsyncListener> for OwnedFd {
+    type Error = io::Error;
+
+    fn try_from(listener: AsyncListener) -> io::Result<Self> {
+        listener.into_std().map(OwnedFd::from)
+    }
+}
+
+#[cfg(windows)]
+impl AsSocket for AsyncStream {
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.0.as_socket()
+    }
+}
+#[cfg(windows)]
+impl AsRawSocket for AsyncStream {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.0.as_raw_socket()
+    }
+}
+#[cfg(windows)]
+impl TryFrom<OwnedSocket> for AsyncStream {
+    type Error = io::Error;
+
+    fn try_from(socket: OwnedSocket) -> io::Result<Self> {
+        Self::from_std(UnixStream::from(socket))
+    }
+}
+#[cfg(windows)]
+impl TryFrom<AsyncStream> for OwnedSocket {
+    type Error = io::Error;
+
+    fn try_from(stream: AsyncStream) -> io::Result<Self> {
+        stream.into_std().map(OwnedSocket::from)
+    }
+}
+#[cfg(windows)]
+impl AsSocket for AsyncListener {
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.0.as_socket()
+    }
+}
+#[cfg(windows)]
+impl AsRawSocket for AsyncListener {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.0.as_raw_socket()
+    }
+}
+#[cfg(windows)]
+impl TryFrom<OwnedSocket> for AsyncListener {
+    type Error = io::Error;
+
+    fn try_from(socket: OwnedSocket) -> io::Result<Self> {
+        Self::from_std(UnixListener::from(socket))
+    }
+}
+#[cfg(windows)]
+impl TryFrom<AsyncListener> for OwnedSocket {
+    type Error = io::Error;
+
+    fn try_from(listener: AsyncListener) -> io::Result<Self> {
+        listener.into_std().map(OwnedSocket::from)
+    }
 }
@@ -0,0 +1,157 @@
+use crate::error::{Op, with_context};
+use crate::net::listener::{RECLAIM_PROBE_TIMEOUT, SocketFile, reclaim};
+use crate::net::{SockAddr, SocketAddr, UnixListener, UnixStream, sys, validate_path};
+use std::{fs, io, path::Path, sync::Arc};
+
+#[cfg(feature = "async")]
+use crate::net::AsyncListener;
+
+/// A builder for [`UnixListener`]s and [`AsyncListener`]s with non-default
+/// settings.
+///
+/// `UnixListener::bind` is equivalent to `UnixListenerBuilder::new().bind`.
+///
+/// [`AsyncListener`]: crate::net::AsyncListener
+///
+/// # Examples
+///
+/// ```ignore
+/// let listener = UnixListenerBuilder::new()
+///     .backlog(1024)
+///     .remove_stale(true)
+///     .permissions(fs::Permissions::from_mode(0o600))
+///     .unlink_on_drop(true)
+///     .bind("/path/to/the/socket")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnixListenerBuilder {
+    backlog: i32,
+    nonblocking: bool,
+    remove_stale: bool,
+    permissions: Option<fs::Permissions>,
+    unlink_on_drop: bool,
+}
+
+impl UnixListenerBuilder {
+    /// Creates a builder with the same settings `UnixListener::bind` uses: a
+    /// backlog of 128, blocking mode, and no socket-file management.
+    pub fn new() -> Self {
+        Self {
+            backlog: 128,
+            nonblocking: false,
+            remove_stale: false,
+            permissions: None,
+            unlink_on_drop: false,
+        }
+    }
+
+    /// Sets the maximum number of pending connections passed to `listen`.
+    ///
+    /// The OS may silently cap it, e.g. to `net.core.somaxconn` on Linux.
+    pub fn backlog(mut self, backlog: i32) -> Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Sets whether the listener is put into non-blocking mode.
+    ///
+    /// Listeners built with [`bind_async`](UnixListenerBuilder::bind_async)
+    /// are always non-blocking.
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+
+    /// Sets whether a socket file left behind by a server that is no longer
+    /// running is removed before binding.
+    ///
+    /// This probes the path like [`UnixListener::bind_reclaim`]: the file is
+    /// only removed if it is a socket and connecting to it is refused. If a
+    /// server is still listening, the bind fails with `AddrInUse`; any other
+    /// kind of file makes it fail as usual. The probe is a blocking connect,
+    /// also for [`bind_async`](UnixListenerBuilder::bind_async), though it
+    /// only waits when the server's backlog is full.
+    pub fn remove_stale(mut self, remove_stale: bool) -> Self {
+        self.remove_stale = remove_stale;
+        self
+    }
+
+    /// Sets the permissions applied to the socket file after binding.
+    ///
+    /// The file briefly exists with the default permissions between `bind`
+    /// and the change, so restrict the parent directory as well if that
+    /// matters. Ignored for abstract addresses.
+    pub fn permissions(mut self, permissions: fs::Permissions) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// Sets whether the socket file is removed when the listener is dropped.
+    ///
+    /// See [`UnixListener::set_unlink_on_drop`] for the details; this can
+    /// also be changed after binding.
+    pub fn unlink_on_drop(mut self, unlink_on_drop: bool) -> Self {
+        self.unlink_on_drop = unlink_on_drop;
+        self
+    }
+
+    /// Creates a `UnixListener` bound to `path` with these settings.
+    pub fn bind<P: AsRef<Path>>(&self, path: P) -> io::Result<UnixListener> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        self.bind_addr(&addr)
+    }
+
+    /// Creates a `UnixListener` bound to `socket_addr` with these settings.
+    ///
+    /// Errors name the address; their `ErrorKind` is that of the OS error.
+    /// The OS error code itself is wrapped, so `raw_os_error` returns `None`
+    /// on them; get it with [`Error::raw_os_error`](crate::Error::raw_os_error).
+    pub fn bind_addr(&self, socket_addr: &SockAddr) -> io::Result<UnixListener> {
+        self.bind_socket(socket_addr)
+            .map_err(|e| with_context(e, Op::Bind, socket_addr))
+    }
+
+    fn bind_socket(&self, socket_addr: &SockAddr) -> io::Result<UnixListener> {
+        let addr = SocketAddr::try_from(socket_addr.clone()).ok();
+        let path = addr.as_ref().and_then(SocketAddr::as_pathname);
+        if let Some(path) = path.filter(|_| self.remove_stale) {
+            let probe = UnixStream::connect_timeout(path, RECLAIM_PROBE_TIMEOUT);
+            reclaim(path, probe.map(drop))?;
+        }
+
+        let s = sys::unix_socket()?;
+        s.bind(socket_addr)?;
+        // The file exists from here on; remove it again if anything fails.
+        let file = path.map(|path| SocketFile::new(path.to_path_buf()));
+        if let (Some(path), Some(permissions)) = (path, &self.permissions) {
+            fs::set_permissions(path, permissions.clone())?;
+        }
+        s.listen(self.backlog)?;
+        if self.nonblocking {
+            s.set_nonblocking(true)?;
+        }
+        if let Some(file) = &file {
+            file.set_unlink(self.unlink_on_drop);
+        }
+        Ok(UnixListener(s, file.map(Arc::new)))
+    }
+
+    /// Creates an `AsyncListener` bound to `path` with these settings.
+    #[cfg(feature = "async")]
+    pub fn bind_async<P: AsRef<Path>>(&self, path: P) -> io::Result<AsyncListener> {
+        AsyncListener::from_std(self.bind(path)?)
+    }
+
+    /// Creates an `AsyncListener` bound to `socket_addr` with these settings.
+    #[cfg(feature = "async")]
+    pub fn bind_addr_async(&self, socket_addr: &SockAddr) -> io::Result<AsyncListener> {
+        AsyncListener::from_std(self.bind_addr(socket_addr)?)
+    }
+}
+
+impl Default for UnixListenerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
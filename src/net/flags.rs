@@ -0,0 +1,59 @@
+use crate::net::sys::{MSG_PEEK, MSG_WAITALL};
+use std::ffi::c_int;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Flags for [`UnixStream::recv_with_flags`].
+///
+/// Flags are combined with `|`:
+///
+/// ```ignore
+/// let flags = MsgFlags::PEEK | MsgFlags::WAIT_ALL;
+/// ```
+///
+/// [`UnixStream::recv_with_flags`]: crate::net::UnixStream::recv_with_flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MsgFlags(c_int);
+
+impl MsgFlags {
+    /// No flags; a plain receive.
+    pub const NONE: MsgFlags = MsgFlags(0);
+    /// `MSG_PEEK`: return data without removing it from the receive queue,
+    /// so the next receive returns it again.
+    pub const PEEK: MsgFlags = MsgFlags(MSG_PEEK as c_int);
+    /// `MSG_WAITALL`: block until the whole buffer is filled, unless the
+    /// connection is closed, an error occurs or a timeout expires first.
+    ///
+    /// Not for sockets in non-blocking mode: Unix ignores it there, but on
+    /// Windows the receive fails with `WSAEOPNOTSUPP`.
+    pub const WAIT_ALL: MsgFlags = MsgFlags(MSG_WAITALL as c_int);
+
+    /// Returns `true` if all flags in `other` are set in `self`.
+    pub fn contains(self, other: MsgFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `self` with the flags in `other` cleared.
+    #[cfg(feature = "async")]
+    pub(crate) fn without(self, other: MsgFlags) -> MsgFlags {
+        MsgFlags(self.0 & !other.0)
+    }
+
+    /// Returns the raw `MSG_*` bits, as passed to `recv`.
+    pub fn bits(self) -> c_int {
+        self.0
+    }
+}
+
+impl BitOr for MsgFlags {
+    type Output = MsgFlags;
+
+    fn bitor(self, rhs: MsgFlags) -> MsgFlags {
+        MsgFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MsgFlags {
+    fn bitor_assign(&mut self, rhs: MsgFlags) {
+        self.0 |= rhs.0;
+    }
+}
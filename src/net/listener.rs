@@ -1,14 +1,112 @@
-use crate::net::{SockAddr, Socket, UnixStream, validate_path};
-use socket2::{Domain, Type};
+use crate::net::sys::{self, FileId, is_out_of_resources};
+use crate::net::{SockAddr, Socket, SocketAddr, UnixListenerBuilder, UnixStream};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{
+    AsRawSocket, AsSocket, FromRawSocket, IntoRawSocket, OwnedSocket, RawSocket,
+};
 use std::{
-    io,
+    fs, io,
     ops::{Deref, DerefMut},
-    os::windows::io::{AsRawSocket, AsSocket, IntoRawSocket},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
 };
-pub struct UnixListener(pub Socket);
+/// A Unix domain socket server, listening for connections.
+///
+/// Build one from an existing socket with `From`; the tuple constructor is
+/// private because the listener also tracks the socket file it bound.
+pub struct UnixListener(
+    #[deprecated(
+        note = "use the inherent methods, or convert into a `socket2::Socket` with `From`"
+    )]
+    pub Socket,
+    pub(crate) Option<Arc<SocketFile>>,
+);
+
+/// How long [`UnixListener::bind_reclaim`] waits for an existing server to
+/// accept its probe connection.
+pub(crate) const RECLAIM_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Removes the socket file at `path` if `probe`, the outcome of connecting to
+/// it, shows that no server is listening there any more.
+pub(crate) fn reclaim(path: &Path, probe: io::Result<()>) -> io::Result<()> {
+    match probe {
+        Ok(()) => Err(in_use_by_live_server()),
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => Err(in_use_by_live_server()),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+            if sys::is_socket(path).unwrap_or(false)
+                && let Err(e) = fs::remove_file(path)
+                && e.kind() != io::ErrorKind::NotFound
+            {
+                return Err(e);
+            }
+            Ok(())
+        }
+        // Nothing there, or nothing we can reason about; let `bind` decide.
+        Err(_) => Ok(()),
+    }
+}
+
+fn in_use_by_live_server() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AddrInUse,
+        "socket path is already in use by a live server",
+    )
+}
+
+/// The socket file a listener bound, which it may remove when dropped.
+///
+/// Clones of the listener share it, so the file stays until the last of them
+/// is gone.
+pub(crate) struct SocketFile {
+    path: PathBuf,
+    id: Option<FileId>,
+    unlink: AtomicBool,
+}
+
+impl SocketFile {
+    /// Remembers the socket file that was just bound at `path`. It is
+    /// removed on drop unless [`set_unlink`](SocketFile::set_unlink) says
+    /// otherwise.
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            id: sys::file_id(&path).ok(),
+            path,
+            unlink: AtomicBool::new(true),
+        }
+    }
+
+    pub(crate) fn set_unlink(&self, unlink: bool) {
+        self.unlink.store(unlink, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SocketFile {
+    /// Removes the file, but only if it is still the socket this listener
+    /// created rather than one that replaced it in the meantime.
+    fn drop(&mut self) {
+        if self.unlink.load(Ordering::Relaxed)
+            && self.id.is_some()
+            && sys::file_id(&self.path).ok() == self.id
+        {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
 
 impl UnixListener {
+    /// The underlying socket; the public field is deprecated for users only.
+    #[allow(deprecated)]
+    pub(crate) fn socket(&self) -> &Socket {
+        &self.0
+    }
+
     /// Creates a new `UnixListener` bound to the specified socket.
     ///
     /// # Examples
@@ -23,15 +121,68 @@ impl UnixListener {
     /// };
     /// ```
     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        validate_path(&path)?;
-        let addr = SockAddr::unix(path)?;
-        Self::bind_addr(&addr)
+        UnixListenerBuilder::new().bind(path)
     }
+
+    /// Creates a new `UnixListener` bound to the specified socket, taking
+    /// over the socket file of a server that is no longer running.
+    ///
+    /// If something already exists at `path`, this first tries to connect to
+    /// it. When the connection is refused and the file is a socket, the file
+    /// is left over from a server that exited without removing it, so it is
+    /// deleted before binding. Anything else at `path` is left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `AddrInUse` saying the path is "in use by a
+    /// live server" if a server accepts the probe connection (or is too busy
+    /// to answer it in time), besides the errors of [`bind`](UnixListener::bind).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Starts fine even if the previous instance crashed.
+    /// let listener = UnixListener::bind_reclaim("/path/to/the/socket").unwrap();
+    /// ```
+    pub fn bind_reclaim<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        UnixListenerBuilder::new().remove_stale(true).bind(path)
+    }
+
+    /// Creates a new `UnixListener` bound to the given name in the abstract
+    /// namespace.
+    ///
+    /// No socket file is created, so nothing has to be removed afterwards.
+    /// See [`SocketAddr::from_abstract_name`] for platform support.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind_abstract(b"hidden").unwrap();
+    /// ```
+    pub fn bind_abstract<N: AsRef<[u8]>>(name: N) -> io::Result<Self> {
+        let addr = SocketAddr::from_abstract_name(name)?;
+        Self::bind_addr(addr.as_ref())
+    }
+    /// Creates a new `UnixListener` bound to `socket_addr`.
+    ///
+    /// See [`UnixListenerBuilder::bind_addr`] for how errors are reported.
     pub fn bind_addr(socket_addr: &SockAddr) -> io::Result<Self> {
-        let s = Socket::new(Domain::UNIX, Type::STREAM, None)?;
-        s.bind(socket_addr)?;
-        s.listen(128)?;
-        Ok(Self(s))
+        UnixListenerBuilder::new().bind_addr(socket_addr)
+    }
+
+    /// Returns a [`UnixListenerBuilder`] for binding a listener with
+    /// non-default settings, such as the backlog.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::builder()
+    ///     .backlog(1024)
+    ///     .bind("/path/to/the/socket")
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> UnixListenerBuilder {
+        UnixListenerBuilder::new()
     }
     /// Accepts a new incoming connection to this listener.
     ///
@@ -51,9 +202,78 @@ impl UnixListener {
     ///     Err(e) => println!("accept function failed: {:?}", e),
     /// }
     /// ```
-    pub fn accept(&self) -> io::Result<(UnixStream, SockAddr)> {
-        let (s, addr) = self.0.accept()?;
-        Ok((UnixStream(s), addr))
+    pub fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        let (s, addr) = self.socket().accept()?;
+        Ok((UnixStream(s), SocketAddr::new(addr)))
+    }
+
+    /// Returns an iterator over incoming connections.
+    ///
+    /// The iterator will never return [`None`] and will also not yield the
+    /// peer's [`SocketAddr`] structure. Transient `accept` errors are yielded
+    /// like any other error unless a different
+    /// [`TransientErrorPolicy`] is chosen with
+    /// [`Incoming::on_transient_error`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    ///
+    /// for stream in listener.incoming() {
+    ///     match stream {
+    ///         Ok(stream) => {
+    ///             thread::spawn(|| handle_client(stream));
+    ///         }
+    ///         Err(err) => {
+    ///             break;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming {
+            listener: self,
+            policy: TransientErrorPolicy::default(),
+        }
+    }
+
+    /// Turns a `UnixListener` into an iterator over incoming connections.
+    ///
+    /// Behaves like [`incoming`](UnixListener::incoming), but the iterator
+    /// owns the listener, so it can be moved into another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    ///
+    /// thread::spawn(move || {
+    ///     for stream in listener.into_incoming().on_transient_error(TransientErrorPolicy::Retry) {
+    ///         thread::spawn(|| handle_client(stream.unwrap()));
+    ///     }
+    /// });
+    /// ```
+    pub fn into_incoming(self) -> IntoIncoming {
+        IntoIncoming {
+            listener: self,
+            policy: TransientErrorPolicy::default(),
+        }
+    }
+
+    /// Accepts a connection, applying `policy` to transient errors.
+    fn accept_with(&self, policy: TransientErrorPolicy) -> io::Result<UnixStream> {
+        loop {
+            match self.accept() {
+                Ok((stream, _addr)) => return Ok(stream),
+                Err(e) if is_transient(&e) => match policy {
+                    TransientErrorPolicy::Yield => return Err(e),
+                    TransientErrorPolicy::Retry => {}
+                    TransientErrorPolicy::Backoff(delay) => thread::sleep(delay),
+                },
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Creates a new independently owned handle to the underlying socket.
@@ -61,6 +281,9 @@ impl UnixListener {
     /// The returned `UnixListener` is a reference to the same socket that this
     /// object references. Both handles can be used to accept incoming
     /// connections and options set on one listener will affect the other.
+    /// They share the socket file too, which is removed on drop (if
+    /// [`set_unlink_on_drop`](UnixListener::set_unlink_on_drop) says so)
+    /// only once both are dropped.
     ///
     /// # Examples
     ///
@@ -70,31 +293,280 @@ impl UnixListener {
     /// let listener_copy = listener.try_clone().expect("Couldn't clone socket");
     /// ```
     pub fn try_clone(&self) -> io::Result<UnixListener> {
-        self.0.try_clone().map(UnixListener)
+        self.socket()
+            .try_clone()
+            .map(|s| UnixListener(s, self.1.clone()))
     }
+
+    /// Sets whether the socket file is removed when this listener is dropped.
+    ///
+    /// The file is only removed if it is still the socket this listener
+    /// created; if it was deleted or replaced in the meantime, for example by
+    /// another server binding the same path, it is left alone. Clones made
+    /// with [`try_clone`](UnixListener::try_clone) share the setting, and the
+    /// file is only removed once the last of them is dropped. This has no
+    /// effect on listeners without a socket file of their own, such as
+    /// listeners bound to abstract addresses. See also
+    /// [`UnixListenerBuilder::unlink_on_drop`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    /// listener.set_unlink_on_drop(true);
+    /// drop(listener); // "/path/to/the/socket" is gone
+    /// ```
+    pub fn set_unlink_on_drop(&self, unlink: bool) {
+        if let Some(file) = &self.1 {
+            file.set_unlink(unlink);
+        }
+    }
+
+    /// Moves the socket into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, `accept` fails with `ErrorKind::WouldBlock`
+    /// when no connection is pending.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    /// listener.set_nonblocking(true).expect("Couldn't set non blocking");
+    /// ```
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.socket().set_nonblocking(nonblocking)
+    }
+
+    /// Returns the value of the `SO_ERROR` option, clearing it.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.socket().take_error()
+    }
+
+    /// Returns the local socket address of this listener.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    ///
+    /// let addr = listener.local_addr().expect("Couldn't get local address");
+    /// ```
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket().local_addr().map(SocketAddr::new)
+    }
+}
+
+/// What the connection iterators do when `accept` fails with a transient error.
+///
+/// An error is transient if it concerns a single connection that went away
+/// before it could be accepted (`ConnectionAborted`, `ConnectionReset`,
+/// `Interrupted`), or if the process or system ran out of descriptors or
+/// buffer space (`EMFILE`/`WSAEMFILE`, `ENFILE`, `ENOBUFS`/`WSAENOBUFS`,
+/// `ENOMEM`). All other errors are always yielded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransientErrorPolicy {
+    /// Yield the error to the caller, like `std`'s `Incoming` does.
+    #[default]
+    Yield,
+    /// Skip the error and accept again immediately.
+    ///
+    /// Running out of descriptors makes every `accept` fail until some are
+    /// closed, so this busy-loops in that case; prefer
+    /// [`Backoff`](TransientErrorPolicy::Backoff) for long-running servers.
+    Retry,
+    /// Skip the error, sleep for the given duration and accept again.
+    Backoff(Duration),
 }
+
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+    ) || is_out_of_resources(e)
+}
+
+/// An iterator over incoming connections to a [`UnixListener`].
+///
+/// It will never return [`None`].
+///
+/// This `struct` is created by [`UnixListener::incoming`].
+pub struct Incoming<'a> {
+    listener: &'a UnixListener,
+    policy: TransientErrorPolicy,
+}
+
+impl Incoming<'_> {
+    /// Sets how transient `accept` errors are handled.
+    pub fn on_transient_error(mut self, policy: TransientErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl Iterator for Incoming<'_> {
+    type Item = io::Result<UnixStream>;
+
+    fn next(&mut self) -> Option<io::Result<UnixStream>> {
+        Some(self.listener.accept_with(self.policy))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// An owned iterator over incoming connections to a [`UnixListener`].
+///
+/// It will never return [`None`].
+///
+/// This `struct` is created by [`UnixListener::into_incoming`].
+pub struct IntoIncoming {
+    listener: UnixListener,
+    policy: TransientErrorPolicy,
+}
+
+impl IntoIncoming {
+    /// Sets how transient `accept` errors are handled.
+    pub fn on_transient_error(mut self, policy: TransientErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl Iterator for IntoIncoming {
+    type Item = io::Result<UnixStream>;
+
+    fn next(&mut self) -> Option<io::Result<UnixStream>> {
+        Some(self.listener.accept_with(self.policy))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(windows)]
 impl AsSocket for UnixListener {
     fn as_socket(&self) -> std::os::windows::prelude::BorrowedSocket<'_> {
-        self.0.as_socket()
+        self.socket().as_socket()
     }
 }
+#[cfg(windows)]
 impl AsRawSocket for UnixListener {
     fn as_raw_socket(&self) -> std::os::windows::prelude::RawSocket {
-        self.0.as_raw_socket()
+        self.socket().as_raw_socket()
     }
 }
+#[cfg(windows)]
 impl IntoRawSocket for UnixListener {
     fn into_raw_socket(self) -> std::os::windows::prelude::RawSocket {
-        self.0.into_raw_socket()
+        Socket::from(self).into_raw_socket()
+    }
+}
+#[cfg(windows)]
+impl FromRawSocket for UnixListener {
+    /// Wraps a raw socket, which must be a listening `AF_UNIX` stream socket.
+    ///
+    /// # Safety
+    ///
+    /// `socket` must be an open socket that nothing else owns.
+    unsafe fn from_raw_socket(socket: RawSocket) -> Self {
+        // SAFETY: guaranteed by the caller.
+        UnixListener(unsafe { Socket::from_raw_socket(socket) }, None)
+    }
+}
+#[cfg(windows)]
+impl From<OwnedSocket> for UnixListener {
+    fn from(socket: OwnedSocket) -> Self {
+        UnixListener(Socket::from(socket), None)
+    }
+}
+#[cfg(windows)]
+impl From<UnixListener> for OwnedSocket {
+    fn from(listener: UnixListener) -> Self {
+        Socket::from(listener).into()
     }
 }
+#[cfg(unix)]
+impl AsFd for UnixListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket().as_fd()
+    }
+}
+#[cfg(unix)]
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket().as_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl IntoRawFd for UnixListener {
+    fn into_raw_fd(self) -> RawFd {
+        Socket::from(self).into_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl FromRawFd for UnixListener {
+    /// Wraps a raw file descriptor, which must be a listening `AF_UNIX`
+    /// stream socket.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open socket that nothing else owns.
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        // SAFETY: guaranteed by the caller.
+        UnixListener(unsafe { Socket::from_raw_fd(fd) }, None)
+    }
+}
+#[cfg(unix)]
+impl From<OwnedFd> for UnixListener {
+    fn from(fd: OwnedFd) -> Self {
+        UnixListener(Socket::from(fd), None)
+    }
+}
+#[cfg(unix)]
+impl From<UnixListener> for OwnedFd {
+    fn from(listener: UnixListener) -> Self {
+        Socket::from(listener).into()
+    }
+}
+impl From<Socket> for UnixListener {
+    /// Wraps a `socket2::Socket`, which must be a listening `AF_UNIX` stream
+    /// socket. The listener doesn't own a socket file, so nothing is removed
+    /// when it is dropped.
+    fn from(socket: Socket) -> Self {
+        UnixListener(socket, None)
+    }
+}
+impl From<UnixListener> for Socket {
+    /// Unwraps the socket. The socket file, if any, is left in place, also
+    /// when clones of the listener are dropped later.
+    fn from(listener: UnixListener) -> Self {
+        #[allow(deprecated)]
+        let UnixListener(s, file) = listener;
+        if let Some(file) = file {
+            file.set_unlink(false);
+        }
+        s
+    }
+}
+/// Deprecated: use the inherent methods instead, or convert into a
+/// `socket2::Socket` with `From` for anything they don't cover. Derefing
+/// exposes calls that make no sense on a listener and ties the public API to
+/// the `socket2` version; this impl will be removed in a future release.
+// `#[deprecated]` has no effect on trait impls, so this is documented only;
+// the `.0` field it derefs to is deprecated for real.
 impl Deref for UnixListener {
     type Target = Socket;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.socket()
     }
 }
+/// Deprecated along with the [`Deref`] impl.
 impl DerefMut for UnixListener {
+    #[allow(deprecated)]
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -0,0 +1,127 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io,
+    time::{Duration, Instant},
+};
+
+/// How [`UnixStream::connect_with_retry`] waits for a server to come up.
+///
+/// Attempts that fail because nobody is listening yet (`NotFound` or
+/// `ConnectionRefused`) are retried with exponential backoff until `timeout`
+/// has passed since the first attempt. Any other error, such as
+/// `PermissionDenied`, is returned immediately.
+///
+/// [`UnixStream::connect_with_retry`]: crate::net::UnixStream::connect_with_retry
+///
+/// # Examples
+///
+/// ```ignore
+/// let policy = RetryPolicy::new(Duration::from_secs(10))
+///     .initial_delay(Duration::from_millis(50))
+///     .max_delay(Duration::from_secs(1));
+/// let stream = UnixStream::connect_with_retry("/tmp/sock", policy).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    timeout: Duration,
+    initial_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy that keeps retrying for up to `timeout`.
+    ///
+    /// The first pause is 10ms, pauses double up to 1s, and jitter is on.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_secs(1),
+            jitter: true,
+        }
+    }
+
+    /// Sets the pause after the first failed attempt.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the longest pause between two attempts.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets whether each pause is randomly shortened by up to half, so that
+    /// many clients started together don't retry in lockstep.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retries for up to 30 seconds.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+/// Bookkeeping for one `connect_with_retry` call.
+pub(crate) struct Attempts {
+    policy: RetryPolicy,
+    deadline: Instant,
+    delay: Duration,
+    attempt: u32,
+    rng: RandomState,
+}
+
+impl Attempts {
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self {
+            deadline: Instant::now() + policy.timeout,
+            delay: policy.initial_delay,
+            policy,
+            attempt: 0,
+            rng: RandomState::new(),
+        }
+    }
+
+    /// Time the next attempt may take. Never zero, so there is always at
+    /// least one attempt.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1))
+    }
+
+    /// Decides what to do after a failed attempt: returns the pause before
+    /// the next one, or the error to give up with.
+    pub(crate) fn on_error(&mut self, e: io::Error) -> io::Result<Duration> {
+        if !matches!(
+            e.kind(),
+            io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+        ) {
+            return Err(e);
+        }
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, e));
+        }
+
+        self.attempt += 1;
+        let mut pause = self.delay;
+        if self.policy.jitter {
+            // `RandomState` is seeded from the OS, which is plenty for jitter.
+            let mut hasher = self.rng.build_hasher();
+            hasher.write_u32(self.attempt);
+            let half = pause / 2;
+            pause = half + half.mul_f64((hasher.finish() >> 11) as f64 / (1u64 << 53) as f64);
+        }
+        self.delay = (self.delay * 2).min(self.policy.max_delay);
+        Ok(pause.min(left))
+    }
+}
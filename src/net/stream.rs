@@ -1,11 +1,43 @@
-use crate::net::{SockAddr, Socket, validate_path};
-use socket2::{Domain, Type};
+use crate::error::{Op, with_context};
+use crate::net::retry::Attempts;
+use crate::net::{
+    MsgFlags, RetryPolicy, SockAddr, Socket, SocketAddr, UCred, sys, ucred, validate_path,
+};
+use std::mem::MaybeUninit;
+use std::net::Shutdown;
 use std::ops::{Deref, DerefMut};
-use std::os::windows::io::{AsRawSocket, AsSocket, IntoRawSocket};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{
+    AsRawSocket, AsSocket, FromRawSocket, IntoRawSocket, OwnedSocket, RawSocket,
+};
+use std::thread;
+use std::time::{Duration, Instant};
 use std::{io, path::Path};
 
-pub struct UnixStream(pub Socket);
+/// Longest pause between connect attempts while a listener's backlog is full.
+pub(crate) const MAX_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// A Unix domain stream socket.
+pub struct UnixStream(
+    #[deprecated(
+        note = "use the inherent methods, or convert into a `socket2::Socket` with `From`"
+    )]
+    pub Socket,
+);
 impl UnixStream {
+    /// The underlying socket; the public field is deprecated for users only.
+    #[allow(deprecated)]
+    pub(crate) fn socket(&self) -> &Socket {
+        &self.0
+    }
+
+    #[allow(deprecated)]
+    fn into_socket(self) -> Socket {
+        self.0
+    }
+
     /// Connects to the socket named by `path`.
     ///
     /// # Examples
@@ -24,10 +56,358 @@ impl UnixStream {
         let addr = SockAddr::unix(path)?;
         Self::connect_addr(&addr)
     }
+    /// Connects to the socket bound to `name` in the abstract namespace.
+    ///
+    /// See [`SocketAddr::from_abstract_name`] for platform support.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect_abstract(b"hidden").unwrap();
+    /// ```
+    pub fn connect_abstract<N: AsRef<[u8]>>(name: N) -> io::Result<Self> {
+        let addr = SocketAddr::from_abstract_name(name)?;
+        Self::connect_addr(addr.as_ref())
+    }
+    /// Connects to the socket at `socket_addr`.
+    ///
+    /// Errors name the address; their `ErrorKind` is that of the OS error.
+    /// The OS error code itself is wrapped, so `raw_os_error` returns `None`
+    /// on them; get it with [`Error::raw_os_error`](crate::Error::raw_os_error).
     pub fn connect_addr(socket_addr: &SockAddr) -> io::Result<Self> {
-        let s = Socket::new(Domain::UNIX, Type::STREAM, None)?;
-        s.connect(socket_addr)?;
-        Ok(Self(s))
+        let connect = || {
+            let s = sys::unix_socket()?;
+            s.connect(socket_addr)?;
+            Ok(UnixStream(s))
+        };
+        connect().map_err(|e| with_context(e, Op::Connect, socket_addr))
+    }
+
+    /// Connects to the socket named by `path`, giving up after `timeout`.
+    ///
+    /// Unlike [`connect`](UnixStream::connect), this never waits longer than
+    /// `timeout` for an overloaded or unresponsive listener. The socket is
+    /// connected in non-blocking mode and then waited on with `poll` (Unix)
+    /// or `WSAPoll` (Windows); while the listener's backlog is full the
+    /// connect is retried until the deadline. The returned stream is in
+    /// blocking mode.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `ErrorKind::TimedOut` if the connection isn't established
+    /// in time, and with `ErrorKind::InvalidInput` if `timeout` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect_timeout("/tmp/sock", Duration::from_secs(1)).unwrap();
+    /// ```
+    pub fn connect_timeout<P: AsRef<Path>>(path: P, timeout: Duration) -> io::Result<Self> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        Self::connect_addr_timeout(&addr, timeout)
+    }
+
+    /// Connects to the socket at `socket_addr`, giving up after `timeout`.
+    ///
+    /// See [`connect_timeout`](UnixStream::connect_timeout).
+    pub fn connect_addr_timeout(socket_addr: &SockAddr, timeout: Duration) -> io::Result<Self> {
+        if timeout.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot set a 0 duration timeout",
+            ));
+        }
+        Self::connect_deadline(socket_addr, Instant::now() + timeout)
+            .map_err(|e| with_context(e, Op::Connect, socket_addr))
+    }
+
+    fn connect_deadline(socket_addr: &SockAddr, deadline: Instant) -> io::Result<Self> {
+        let mut delay = Duration::from_millis(1);
+        let stream = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match Self::start_connect(socket_addr) {
+                Ok(stream) => break stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    if remaining.is_zero() {
+                        return Err(io::ErrorKind::TimedOut.into());
+                    }
+                    thread::sleep(delay.min(remaining));
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        while !stream.finish_connect()? {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            sys::poll_writable(stream.socket(), remaining)?;
+        }
+        stream.socket().set_nonblocking(false)?;
+        Ok(stream)
+    }
+
+    /// Connects to the socket named by `path`, waiting for the server to
+    /// come up.
+    ///
+    /// While the connect fails because nobody is listening on `path` yet
+    /// (`NotFound` or `ConnectionRefused`), it is retried with exponential
+    /// backoff and jitter according to `policy`. Other errors, such as
+    /// `PermissionDenied`, are returned right away. No single attempt waits
+    /// past the policy's deadline.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `ErrorKind::TimedOut` once the deadline has passed; the
+    /// error of the last attempt is attached as the inner error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let policy = RetryPolicy::new(Duration::from_secs(10));
+    /// let socket = UnixStream::connect_with_retry("/tmp/sock", policy).unwrap();
+    /// ```
+    pub fn connect_with_retry<P: AsRef<Path>>(path: P, policy: RetryPolicy) -> io::Result<Self> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        let mut attempts = Attempts::new(policy);
+        loop {
+            match Self::connect_addr_timeout(&addr, attempts.remaining()) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => thread::sleep(attempts.on_error(e)?),
+            }
+        }
+    }
+
+    /// Starts connecting to the socket named by `path` without blocking.
+    ///
+    /// The returned stream is in non-blocking mode and may still be
+    /// connecting. Wait until it is writable, for example with `mio` or
+    /// `polling`, then call [`finish_connect`](UnixStream::finish_connect)
+    /// to learn whether the connection was established.
+    ///
+    /// # Errors
+    ///
+    /// Errors that are known right away, such as `NotFound` or
+    /// `ConnectionRefused`, are returned directly. On Unix, a listener whose
+    /// backlog is full makes this fail with `ErrorKind::WouldBlock`; nothing
+    /// is in flight then, so try again later.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect_nonblocking("/tmp/sock").unwrap();
+    /// // register `socket` for writability with the event loop, and once it
+    /// // fires:
+    /// if socket.finish_connect()? {
+    ///     // connected
+    /// }
+    /// ```
+    pub fn connect_nonblocking<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        validate_path(&path)?;
+        let addr = SockAddr::unix(path)?;
+        Self::connect_addr_nonblocking(&addr)
+    }
+
+    /// Starts connecting to the socket at `socket_addr` without blocking.
+    ///
+    /// See [`connect_nonblocking`](UnixStream::connect_nonblocking).
+    pub fn connect_addr_nonblocking(socket_addr: &SockAddr) -> io::Result<Self> {
+        Self::start_connect(socket_addr).map_err(|e| with_context(e, Op::Connect, socket_addr))
+    }
+
+    /// [`connect_addr_nonblocking`](UnixStream::connect_addr_nonblocking)
+    /// without naming the address in errors.
+    pub(crate) fn start_connect(socket_addr: &SockAddr) -> io::Result<Self> {
+        let s = sys::unix_socket()?;
+        s.set_nonblocking(true)?;
+        if let Err(e) = s.connect(socket_addr) {
+            sys::connect_in_progress(e)?;
+        }
+        Ok(UnixStream(s))
+    }
+
+    /// Checks on a connect started with
+    /// [`connect_nonblocking`](UnixStream::connect_nonblocking).
+    ///
+    /// Returns `Ok(true)` once the connection is established and `Ok(false)`
+    /// while it is still in progress, in which case wait until the stream is
+    /// writable and call this again. If the connect failed, its error is
+    /// returned; it is taken from [`take_error`](UnixStream::take_error), so
+    /// it is only reported once.
+    pub fn finish_connect(&self) -> io::Result<bool> {
+        if let Some(e) = self.take_error()? {
+            return Err(e);
+        }
+        match self.socket().peer_addr() {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotConnected => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the value of the `SO_ERROR` option, clearing it.
+    ///
+    /// This is where the outcome of a failed non-blocking connect ends up.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.socket().take_error()
+    }
+
+    /// Sets the read timeout to the timeout specified.
+    ///
+    /// If the value specified is `None`, then `read` calls will block
+    /// indefinitely. An expired timeout is reported as `ErrorKind::TimedOut`
+    /// on every platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ErrorKind::InvalidInput` error if a zero `Duration` is
+    /// passed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.set_read_timeout(Some(Duration::new(1, 0))).expect("Couldn't set read timeout");
+    /// ```
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        check_timeout(timeout)?;
+        self.socket().set_read_timeout(timeout)
+    }
+
+    /// Returns the read timeout of this socket.
+    pub fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        self.socket().read_timeout()
+    }
+
+    /// Sets the write timeout to the timeout specified.
+    ///
+    /// If the value specified is `None`, then `write` calls will block
+    /// indefinitely. An expired timeout is reported as `ErrorKind::TimedOut`
+    /// on every platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `ErrorKind::InvalidInput` error if a zero `Duration` is
+    /// passed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.set_write_timeout(Some(Duration::new(1, 0))).expect("Couldn't set write timeout");
+    /// ```
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        check_timeout(timeout)?;
+        self.socket().set_write_timeout(timeout)
+    }
+
+    /// Returns the write timeout of this socket.
+    pub fn write_timeout(&self) -> io::Result<Option<Duration>> {
+        self.socket().write_timeout()
+    }
+
+    /// Moves the socket into or out of non-blocking mode.
+    ///
+    /// In non-blocking mode, reads and writes that can't complete right away
+    /// fail with `ErrorKind::WouldBlock`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.set_nonblocking(true).expect("Couldn't set nonblocking");
+    /// ```
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.socket().set_nonblocking(nonblocking)
+    }
+
+    /// Sets the size of the send buffer (`SO_SNDBUF`).
+    ///
+    /// The OS may round the value or apply limits; read it back with
+    /// [`send_buffer_size`](UnixStream::send_buffer_size).
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.socket().set_send_buffer_size(size)
+    }
+
+    /// Returns the size of the send buffer (`SO_SNDBUF`).
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.socket().send_buffer_size()
+    }
+
+    /// Sets the size of the receive buffer (`SO_RCVBUF`).
+    ///
+    /// The OS may round the value or apply limits; read it back with
+    /// [`recv_buffer_size`](UnixStream::recv_buffer_size).
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.socket().set_recv_buffer_size(size)
+    }
+
+    /// Returns the size of the receive buffer (`SO_RCVBUF`).
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.socket().recv_buffer_size()
+    }
+
+    /// Sets the `SO_LINGER` option.
+    ///
+    /// With `Some(timeout)`, dropping the stream waits up to `timeout` for
+    /// unsent data to be delivered; `None` restores the default.
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.socket().set_linger(linger)
+    }
+
+    /// Returns the `SO_LINGER` option.
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.socket().linger()
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    ///
+    /// Returns two `UnixStream`s which are connected to each other. On Unix
+    /// this is `socketpair(2)`. Windows has no `socketpair`, so a listener is
+    /// bound to a random abstract name, connected to and accepted from; no
+    /// file is created. Connections from other processes that guessed the
+    /// name are dropped, so both ends always belong to this process.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (sock1, sock2) = match UnixStream::pair() {
+    ///     Ok((sock1, sock2)) => (sock1, sock2),
+    ///     Err(e) => {
+    ///         println!("Couldn't create a pair of sockets: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    #[cfg(unix)]
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        let (a, b) = sys::unix_socket_pair()?;
+        Ok((UnixStream(a), UnixStream(b)))
+    }
+    #[cfg(windows)]
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        use crate::net::UnixListener;
+
+        let mut nonce = [0; 8];
+        sys::random_bytes(&mut nonce)?;
+        let name = format!(
+            "win_uds-pair-{}-{:016x}",
+            std::process::id(),
+            u64::from_ne_bytes(nonce)
+        );
+        let listener = UnixListener::bind_abstract(&name)?;
+        let a = UnixStream::connect_abstract(&name)?;
+        // Connections are accepted in order, so only the ones that beat `a`
+        // into the backlog are skipped before it turns up.
+        loop {
+            let (b, _) = listener.accept()?;
+            if b.peer_cred()?.pid == Some(std::process::id()) {
+                return Ok((a, b));
+            }
+        }
     }
 
     /// Creates a new independently owned handle to the underlying socket.
@@ -44,46 +424,283 @@ impl UnixStream {
     /// let sock_copy = socket.try_clone().expect("Couldn't clone socket");
     /// ```
     pub fn try_clone(&self) -> io::Result<UnixStream> {
-        self.0.try_clone().map(UnixStream)
+        self.socket().try_clone().map(UnixStream)
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let addr = socket.local_addr().expect("Couldn't get local address");
+    /// ```
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket().local_addr().map(SocketAddr::new)
     }
+
+    /// Returns the socket address of the remote half of this connection.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let addr = socket.peer_addr().expect("Couldn't get peer address");
+    /// assert_eq!(addr.as_pathname(), Some(Path::new("/tmp/sock")));
+    /// ```
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.socket().peer_addr().map(SocketAddr::new)
+    }
+
+    /// Gets the credentials of the process on the other end of this
+    /// connection.
+    ///
+    /// See [`UCred`] for which fields each platform fills in. On Windows this
+    /// uses `SIO_AF_UNIX_GETPEERPID`, on Linux `SO_PEERCRED`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let peer_cred = socket.peer_cred().expect("Couldn't get peer credentials");
+    /// ```
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        ucred::peer_cred(self.socket())
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// Shutting down the write half makes the peer read EOF once it has
+    /// received everything sent before, while this side can still read the
+    /// response.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.shutdown(Shutdown::Both).expect("shutdown function failed");
+    /// ```
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.socket().shutdown(how)
+    }
+
+    /// Receives data without removing it from the queue, so the next read
+    /// returns the same bytes again.
+    ///
+    /// Returns the number of bytes peeked, which may be less than the amount
+    /// queued. Blocks like `read` if no data is available.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let mut buf = [0; 10];
+    /// let len = socket.peek(&mut buf).expect("peek failed");
+    /// ```
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_with_flags(buf, MsgFlags::PEEK)
+    }
+
+    /// Receives data with the given `MSG_*` flags.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let mut header = [0; 16];
+    /// // Wait for the whole header instead of looping on partial reads.
+    /// let len = socket.recv_with_flags(&mut header, MsgFlags::WAIT_ALL).unwrap();
+    /// ```
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: MsgFlags) -> io::Result<usize> {
+        // SAFETY: `recv` never writes uninitialised bytes into the buffer, so
+        // it stays initialised.
+        let buf = unsafe { &mut *(buf as *mut [u8] as *mut [MaybeUninit<u8>]) };
+        self.socket()
+            .recv_with_flags(buf, flags.bits())
+            .map_err(|e| self.map_timeout(e))
+    }
+
+    /// Reports an expired read or write timeout as `TimedOut` on every platform.
+    ///
+    /// Unix returns `EAGAIN` when `SO_RCVTIMEO`/`SO_SNDTIMEO` expires, which is
+    /// indistinguishable from a non-blocking socket that isn't ready, so the
+    /// socket's blocking mode decides which of the two happened.
+    #[cfg(unix)]
+    fn map_timeout(&self, e: io::Error) -> io::Error {
+        if e.kind() == io::ErrorKind::WouldBlock && !self.socket().nonblocking().unwrap_or(true) {
+            io::Error::from(io::ErrorKind::TimedOut)
+        } else {
+            e
+        }
+    }
+    #[cfg(windows)]
+    fn map_timeout(&self, e: io::Error) -> io::Error {
+        e
+    }
+}
+/// Rejects a zero timeout like `std` does, instead of letting the OS
+/// interpret it as "no timeout".
+fn check_timeout(timeout: Option<Duration>) -> io::Result<()> {
+    if timeout == Some(Duration::ZERO) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot set a 0 duration timeout",
+        ));
+    }
+    Ok(())
 }
+/// Deprecated: use the inherent methods instead, or convert into a
+/// `socket2::Socket` with `From` for anything they don't cover. Derefing
+/// exposes calls that make no sense on a stream and ties the public API to
+/// the `socket2` version; this impl will be removed in a future release.
+// `#[deprecated]` has no effect on trait impls, so this is documented only;
+// the `.0` field it derefs to is deprecated for real.
 impl Deref for UnixStream {
     type Target = Socket;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.socket()
     }
 }
+/// Deprecated along with the [`Deref`] impl.
 impl DerefMut for UnixStream {
+    #[allow(deprecated)]
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 impl io::Write for UnixStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        io::Write::write(&mut **self, buf)
+        io::Write::write(&mut &*self, buf)
+    }
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        io::Write::write_vectored(&mut &*self, bufs)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        io::Write::flush(&mut &*self)
+    }
+}
+impl io::Write for &UnixStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::Write::write(&mut self.socket(), buf).map_err(|e| self.map_timeout(e))
+    }
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        io::Write::write_vectored(&mut self.socket(), bufs).map_err(|e| self.map_timeout(e))
     }
     fn flush(&mut self) -> io::Result<()> {
-        io::Write::flush(&mut **self)
+        io::Write::flush(&mut self.socket())
     }
 }
 impl io::Read for UnixStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        io::Read::read(&mut **self, buf)
+        io::Read::read(&mut &*self, buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        io::Read::read_vectored(&mut &*self, bufs)
+    }
+}
+impl io::Read for &UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io::Read::read(&mut self.socket(), buf).map_err(|e| self.map_timeout(e))
+    }
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        io::Read::read_vectored(&mut self.socket(), bufs).map_err(|e| self.map_timeout(e))
     }
 }
+#[cfg(windows)]
 impl AsSocket for UnixStream {
     fn as_socket(&self) -> std::os::windows::prelude::BorrowedSocket<'_> {
-        self.0.as_socket()
+        self.socket().as_socket()
     }
 }
+#[cfg(windows)]
 impl AsRawSocket for UnixStream {
     fn as_raw_socket(&self) -> std::os::windows::prelude::RawSocket {
-        self.0.as_raw_socket()
+        self.socket().as_raw_socket()
     }
 }
+#[cfg(windows)]
 impl IntoRawSocket for UnixStream {
     fn into_raw_socket(self) -> std::os::windows::prelude::RawSocket {
-        self.0.into_raw_socket()
+        self.into_socket().into_raw_socket()
+    }
+}
+#[cfg(windows)]
+impl FromRawSocket for UnixStream {
+    /// Wraps a raw socket, which must be a connected `AF_UNIX` stream socket.
+    ///
+    /// # Safety
+    ///
+    /// `socket` must be an open socket that nothing else owns.
+    unsafe fn from_raw_socket(socket: RawSocket) -> Self {
+        // SAFETY: guaranteed by the caller.
+        UnixStream::from(unsafe { Socket::from_raw_socket(socket) })
+    }
+}
+#[cfg(windows)]
+impl From<OwnedSocket> for UnixStream {
+    fn from(socket: OwnedSocket) -> Self {
+        UnixStream::from(Socket::from(socket))
+    }
+}
+#[cfg(windows)]
+impl From<UnixStream> for OwnedSocket {
+    fn from(stream: UnixStream) -> Self {
+        stream.into_socket().into()
+    }
+}
+#[cfg(unix)]
+impl AsFd for UnixStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket().as_fd()
+    }
+}
+#[cfg(unix)]
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket().as_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl IntoRawFd for UnixStream {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_socket().into_raw_fd()
+    }
+}
+#[cfg(unix)]
+impl FromRawFd for UnixStream {
+    /// Wraps a raw file descriptor, which must be a connected `AF_UNIX`
+    /// stream socket.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open socket that nothing else owns.
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        // SAFETY: guaranteed by the caller.
+        UnixStream::from(unsafe { Socket::from_raw_fd(fd) })
+    }
+}
+#[cfg(unix)]
+impl From<OwnedFd> for UnixStream {
+    fn from(fd: OwnedFd) -> Self {
+        UnixStream::from(Socket::from(fd))
+    }
+}
+#[cfg(unix)]
+impl From<UnixStream> for OwnedFd {
+    fn from(stream: UnixStream) -> Self {
+        stream.into_socket().into()
+    }
+}
+impl From<Socket> for UnixStream {
+    /// Wraps a `socket2::Socket`, which must be a connected `AF_UNIX`
+    /// stream socket.
+    fn from(socket: Socket) -> Self {
+        UnixStream(socket)
+    }
+}
+impl From<UnixStream> for Socket {
+    fn from(stream: UnixStream) -> Self {
+        stream.into_socket()
     }
 }
 
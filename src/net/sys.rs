@@ -0,0 +1,315 @@
+//! Platform definitions shared by the socket types.
+
+#[cfg(unix)]
+pub(crate) use libc::{AF_UNIX, MSG_PEEK, MSG_WAITALL, sockaddr_un};
+#[cfg(windows)]
+pub(crate) use windows_sys::Win32::Networking::WinSock::{
+    AF_UNIX, MSG_PEEK, MSG_WAITALL, SOCKADDR_UN as sockaddr_un,
+};
+
+use crate::error::map_af_unsupported;
+use crate::net::Socket;
+use socket2::{Domain, Type};
+use std::{io, path::Path, time::Duration};
+
+/// Offset of `sun_path` within `sockaddr_un`.
+pub(crate) const SUN_PATH_OFFSET: usize = std::mem::offset_of!(sockaddr_un, sun_path);
+
+/// Capacity of `sun_path`, including the terminating null byte.
+pub(crate) const SUN_PATH_LEN: usize = std::mem::size_of::<sockaddr_un>() - SUN_PATH_OFFSET;
+
+/// Whether the platform supports abstract socket addresses, whose `sun_path`
+/// starts with a null byte and never appears in the filesystem.
+pub(crate) const ABSTRACT_NAMESPACE: bool =
+    cfg!(any(target_os = "linux", target_os = "android", windows));
+
+/// Creates an `AF_UNIX` stream socket.
+///
+/// Fails with `ErrorKind::Unsupported` if the OS has no `AF_UNIX` support,
+/// such as Windows 10 before version 1803.
+pub(crate) fn unix_socket() -> io::Result<Socket> {
+    Socket::new(Domain::UNIX, Type::STREAM, None).map_err(map_af_unsupported)
+}
+
+/// Creates a connected pair of `AF_UNIX` stream sockets with `socketpair`.
+#[cfg(unix)]
+pub(crate) fn unix_socket_pair() -> io::Result<(Socket, Socket)> {
+    Socket::pair(Domain::UNIX, Type::STREAM, None).map_err(map_af_unsupported)
+}
+
+/// Returns `true` if `e` means the process or system ran out of descriptors or
+/// buffer space, which usually clears up once other connections are closed.
+pub(crate) fn is_out_of_resources(e: &io::Error) -> bool {
+    #[cfg(unix)]
+    let codes = [libc::EMFILE, libc::ENFILE, libc::ENOBUFS, libc::ENOMEM];
+    #[cfg(windows)]
+    let codes = [
+        windows_sys::Win32::Networking::WinSock::WSAEMFILE,
+        windows_sys::Win32::Networking::WinSock::WSAENOBUFS,
+    ];
+    e.raw_os_error().is_some_and(|code| codes.contains(&code))
+}
+
+/// Interprets the error from `connect` on a non-blocking socket: returns
+/// `Ok` if the connect is under way (or already done) and the error otherwise.
+///
+/// `AF_UNIX` connects on Unix fail with `EAGAIN`, i.e. `WouldBlock`, instead
+/// of going asynchronous when the listener's backlog is full; that is passed
+/// on as an error because nothing is in flight afterwards.
+#[cfg(unix)]
+pub(crate) fn connect_in_progress(e: io::Error) -> io::Result<()> {
+    match e.raw_os_error() {
+        Some(libc::EINPROGRESS | libc::EALREADY | libc::EINTR | libc::EISCONN) => Ok(()),
+        _ => Err(e),
+    }
+}
+#[cfg(windows)]
+pub(crate) fn connect_in_progress(e: io::Error) -> io::Result<()> {
+    use windows_sys::Win32::Networking::WinSock::{
+        WSAEALREADY, WSAEINPROGRESS, WSAEISCONN, WSAEWOULDBLOCK,
+    };
+    match e.raw_os_error() {
+        Some(WSAEWOULDBLOCK | WSAEINPROGRESS | WSAEALREADY | WSAEISCONN) => Ok(()),
+        _ => Err(e),
+    }
+}
+
+/// Waits until `socket` is writable or has an error pending, for at most
+/// `timeout`.
+///
+/// Fails with `TimedOut` if the timeout expires first. Being interrupted by a
+/// signal counts as a wakeup, so callers must re-check the socket's state.
+#[cfg(unix)]
+pub(crate) fn poll_writable(socket: &Socket, timeout: Duration) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut pollfd = libc::pollfd {
+        fd: socket.as_raw_fd(),
+        events: libc::POLLOUT,
+        revents: 0,
+    };
+    // SAFETY: `pollfd` is a valid array of one `pollfd`.
+    match unsafe { libc::poll(&mut pollfd, 1, timeout_ms(timeout)) } {
+        -1 => match io::Error::last_os_error() {
+            e if e.kind() == io::ErrorKind::Interrupted => Ok(()),
+            e => Err(e),
+        },
+        0 => Err(io::ErrorKind::TimedOut.into()),
+        _ => Ok(()),
+    }
+}
+#[cfg(windows)]
+pub(crate) fn poll_writable(socket: &Socket, timeout: Duration) -> io::Result<()> {
+    use std::os::windows::io::AsRawSocket;
+    use windows_sys::Win32::Networking::WinSock::{POLLWRNORM, SOCKET_ERROR, WSAPOLLFD, WSAPoll};
+
+    let mut pollfd = WSAPOLLFD {
+        fd: socket.as_raw_socket() as _,
+        events: POLLWRNORM,
+        revents: 0,
+    };
+    // SAFETY: `pollfd` is a valid array of one `WSAPOLLFD`.
+    match unsafe { WSAPoll(&mut pollfd, 1, timeout_ms(timeout)) } {
+        SOCKET_ERROR => Err(io::Error::last_os_error()),
+        0 => Err(io::ErrorKind::TimedOut.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Converts `timeout` to whole milliseconds for `poll`, rounding up so short
+/// timeouts don't turn into a busy loop.
+fn timeout_ms(timeout: Duration) -> i32 {
+    timeout.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32
+}
+
+/// Identifies a file independently of its path, so a socket file can be told
+/// apart from another one created at the same path later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileId(u64, u64);
+
+/// Returns `true` if the file at `path` is a Unix domain socket.
+///
+/// Symlinks are not followed.
+#[cfg(unix)]
+pub(crate) fn is_socket(path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::FileTypeExt;
+
+    Ok(std::fs::symlink_metadata(path)?.file_type().is_socket())
+}
+
+/// Returns the identity of the file at `path`, without following symlinks.
+#[cfg(unix)]
+pub(crate) fn file_id(path: &Path) -> io::Result<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(path)?;
+    Ok(FileId(metadata.dev(), metadata.ino()))
+}
+
+/// Opens the file at `path` itself, rather than the target of a reparse
+/// point, for querying its attributes.
+#[cfg(windows)]
+fn open_reparse_point(path: &Path) -> io::Result<std::fs::File> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT,
+    };
+
+    std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+}
+
+#[cfg(windows)]
+pub(crate) fn is_socket(path: &Path) -> io::Result<bool> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_ATTRIBUTE_REPARSE_POINT, FILE_ATTRIBUTE_TAG_INFO, FileAttributeTagInfo,
+        GetFileInformationByHandleEx,
+    };
+    // Socket files are reparse points with this tag, see `afunix.h`.
+    const IO_REPARSE_TAG_AF_UNIX: u32 = 0x8000_0023;
+
+    let file = open_reparse_point(path)?;
+    let mut info = FILE_ATTRIBUTE_TAG_INFO {
+        FileAttributes: 0,
+        ReparseTag: 0,
+    };
+    // SAFETY: `info` is a valid, writable `FILE_ATTRIBUTE_TAG_INFO`.
+    let ok = unsafe {
+        GetFileInformationByHandleEx(
+            file.as_raw_handle(),
+            FileAttributeTagInfo,
+            (&raw mut info).cast(),
+            std::mem::size_of::<FILE_ATTRIBUTE_TAG_INFO>() as u32,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(info.FileAttributes & FILE_ATTRIBUTE_REPARSE_POINT != 0
+        && info.ReparseTag == IO_REPARSE_TAG_AF_UNIX)
+}
+
+#[cfg(windows)]
+pub(crate) fn file_id(path: &Path) -> io::Result<FileId> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, GetFileInformationByHandle,
+    };
+
+    let file = open_reparse_point(path)?;
+    // SAFETY: all zeros is a valid `BY_HANDLE_FILE_INFORMATION`.
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    // SAFETY: `info` is a valid, writable `BY_HANDLE_FILE_INFORMATION`.
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(FileId(
+        info.dwVolumeSerialNumber.into(),
+        (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow),
+    ))
+}
+
+/// Creates a new file at `path` that only its owner can access.
+///
+/// Fails with `ErrorKind::AlreadyExists` if something is already there.
+#[cfg(unix)]
+pub(crate) fn create_private_file(path: &Path) -> io::Result<std::fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+#[cfg(windows)]
+pub(crate) fn create_private_file(path: &Path) -> io::Result<std::fs::File> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::FromRawHandle;
+    use windows_sys::Win32::Foundation::{GENERIC_WRITE, INVALID_HANDLE_VALUE, LocalFree};
+    use windows_sys::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows_sys::Win32::Security::SECURITY_ATTRIBUTES;
+    use windows_sys::Win32::Storage::FileSystem::{CREATE_NEW, CreateFileW, FILE_ATTRIBUTE_NORMAL};
+
+    // A protected DACL, so nothing is inherited from the directory, that
+    // grants full access to the file's owner and nobody else.
+    let sddl: Vec<u16> = "D:P(A;;FA;;;OW)".encode_utf16().chain([0]).collect();
+    let mut descriptor = std::ptr::null_mut();
+    // SAFETY: `sddl` is null-terminated and `descriptor` is a valid out
+    // pointer; the size isn't needed.
+    let ok = unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            sddl.as_ptr(),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor,
+        bInheritHandle: 0,
+    };
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    // SAFETY: `wide` is null-terminated and `attributes` holds the valid
+    // security descriptor created above.
+    let handle = unsafe {
+        CreateFileW(
+            wide.as_ptr(),
+            GENERIC_WRITE,
+            0,
+            &attributes,
+            CREATE_NEW,
+            FILE_ATTRIBUTE_NORMAL,
+            std::ptr::null_mut(),
+        )
+    };
+    let error = io::Error::last_os_error();
+    // SAFETY: `descriptor` was allocated with `LocalAlloc` by
+    // `ConvertStringSecurityDescriptorToSecurityDescriptorW`.
+    unsafe { LocalFree(descriptor) };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(error);
+    }
+    // SAFETY: `handle` is a file handle that was just opened and nothing
+    // else owns.
+    Ok(unsafe { std::fs::File::from_raw_handle(handle) })
+}
+
+/// Fills `buf` with random bytes from the OS.
+#[cfg(unix)]
+pub(crate) fn random_bytes(buf: &mut [u8]) -> io::Result<()> {
+    use std::io::Read;
+
+    std::fs::File::open("/dev/urandom")?.read_exact(buf)
+}
+#[cfg(windows)]
+pub(crate) fn random_bytes(buf: &mut [u8]) -> io::Result<()> {
+    use windows_sys::Win32::Security::Cryptography::{
+        BCRYPT_USE_SYSTEM_PREFERRED_RNG, BCryptGenRandom,
+    };
+
+    // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+    let status = unsafe {
+        BCryptGenRandom(
+            std::ptr::null_mut(),
+            buf.as_mut_ptr(),
+            buf.len() as u32,
+            BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+        )
+    };
+    if status < 0 {
+        return Err(io::Error::other(format!(
+            "BCryptGenRandom failed with status {status:#x}"
+        )));
+    }
+    Ok(())
+}
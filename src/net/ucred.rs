@@ -0,0 +1,153 @@
+use crate::net::Socket;
+use std::io;
+
+/// Credentials of the process on the other end of a [`UnixStream`].
+///
+/// Which fields are filled in depends on the platform:
+///
+/// | Platform                  | `pid` | `uid` | `gid` |
+/// |---------------------------|-------|-------|-------|
+/// | Linux, Android            | yes   | yes   | yes   |
+/// | macOS, iOS                | yes   | yes   | yes   |
+/// | FreeBSD, OpenBSD, NetBSD  | no    | yes   | yes   |
+/// | Windows                   | yes   | no    | no    |
+///
+/// Linux reports the credentials the peer had when it called `connect` or
+/// `listen`; the other platforms report the peer's effective IDs.
+///
+/// [`UnixStream`]: crate::net::UnixStream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UCred {
+    /// The peer's process ID.
+    pub pid: Option<u32>,
+    /// The peer's user ID. Always `None` on Windows.
+    pub uid: Option<u32>,
+    /// The peer's group ID. Always `None` on Windows.
+    pub gid: Option<u32>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn peer_cred(socket: &Socket) -> io::Result<UCred> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut cred = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // SAFETY: `cred` and `len` describe a valid, writable `ucred`.
+    let ret = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            (&raw mut cred).cast(),
+            &mut len,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(UCred {
+        pid: Some(cred.pid as u32),
+        uid: Some(cred.uid),
+        gid: Some(cred.gid),
+    })
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+pub(crate) fn peer_cred(socket: &Socket) -> io::Result<UCred> {
+    use std::os::unix::io::AsRawFd;
+
+    let fd = socket.as_raw_fd();
+    let mut uid: libc::uid_t = 0;
+    let mut gid: libc::gid_t = 0;
+    // SAFETY: `uid` and `gid` are valid, writable locations.
+    if unsafe { libc::getpeereid(fd, &mut uid, &mut gid) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    let pid = {
+        let mut pid: libc::pid_t = 0;
+        let mut len = std::mem::size_of::<libc::pid_t>() as libc::socklen_t;
+        // SAFETY: `pid` and `len` describe a valid, writable `pid_t`.
+        let ret = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_LOCAL,
+                libc::LOCAL_PEERPID,
+                (&raw mut pid).cast(),
+                &mut len,
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Some(pid as u32)
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    let pid = None;
+
+    Ok(UCred {
+        pid,
+        uid: Some(uid),
+        gid: Some(gid),
+    })
+}
+
+#[cfg(windows)]
+pub(crate) fn peer_cred(socket: &Socket) -> io::Result<UCred> {
+    use std::os::windows::io::AsRawSocket;
+    use windows_sys::Win32::Networking::WinSock::{SIO_AF_UNIX_GETPEERPID, SOCKET_ERROR, WSAIoctl};
+
+    let mut pid: u32 = 0;
+    let mut returned: u32 = 0;
+    // SAFETY: the output buffer is a valid, writable `u32`, and the call is
+    // synchronous because no `OVERLAPPED` is passed.
+    let ret = unsafe {
+        WSAIoctl(
+            socket.as_raw_socket() as _,
+            SIO_AF_UNIX_GETPEERPID,
+            std::ptr::null(),
+            0,
+            (&raw mut pid).cast(),
+            std::mem::size_of::<u32>() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+            None,
+        )
+    };
+    if ret == SOCKET_ERROR {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(UCred {
+        pid: Some(pid),
+        uid: None,
+        gid: None,
+    })
+}
+
+#[cfg(not(any(
+    windows,
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+pub(crate) fn peer_cred(_socket: &Socket) -> io::Result<UCred> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "peer credentials are not supported on this platform",
+    ))
+}
@@ -0,0 +1,39 @@
+#![cfg(any(target_os = "linux", target_os = "android", windows))]
+
+use std::io::{Read, Write};
+use win_uds::net::{SocketAddr, UnixListener, UnixStream};
+
+#[test]
+fn abstract_addr() {
+    let addr = SocketAddr::from_abstract_name(b"win_uds\0abstract").unwrap();
+    assert_eq!(addr.as_abstract_name(), Some(&b"win_uds\0abstract"[..]));
+    assert_eq!(addr.as_pathname(), None);
+    assert!(!addr.is_unnamed());
+    assert_eq!(format!("{addr:?}"), "\"win_uds\\x00abstract\" (abstract)");
+}
+
+#[test]
+fn abstract_name_too_long() {
+    assert!(SocketAddr::from_abstract_name(vec![b'o'; 256]).is_err());
+}
+
+#[test]
+fn abstract_echo() {
+    let name = format!("win_uds-abstract-echo-{}", std::process::id());
+    let listener = UnixListener::bind_abstract(&name).unwrap();
+    assert_eq!(
+        listener.local_addr().unwrap().as_abstract_name(),
+        Some(name.as_bytes())
+    );
+
+    let cli = std::thread::spawn(move || {
+        let mut stream = UnixStream::connect_abstract(&name).unwrap();
+        stream.write_all(b"Hello").unwrap();
+    });
+
+    let (mut stream, _addr) = listener.accept().unwrap();
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"Hello");
+    cli.join().unwrap();
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+use win_uds::net::{SockAddr, SocketAddr, UnixListener, UnixStream};
+
+#[test]
+fn pathname_round_trip() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-addr-pathname.sock");
+    let addr = SocketAddr::from_pathname(&path).unwrap();
+    assert_eq!(addr.as_pathname(), Some(path.as_path()));
+    assert!(!addr.is_unnamed());
+    assert_eq!(format!("{addr:?}"), format!("{path:?} (pathname)"));
+    assert_eq!(addr.to_string(), path.display().to_string());
+
+    let sock_addr: SockAddr = addr.into();
+    let addr = SocketAddr::try_from(sock_addr).unwrap();
+    assert_eq!(addr.as_pathname(), Some(path.as_path()));
+}
+
+#[test]
+fn from_pathname_rejects_null_bytes() {
+    assert!(SocketAddr::from_pathname(Path::new("oo\0oo")).is_err());
+}
+
+#[test]
+fn try_from_rejects_other_families() {
+    let inet: SockAddr = "127.0.0.1:0"
+        .parse::<std::net::SocketAddr>()
+        .unwrap()
+        .into();
+    assert!(SocketAddr::try_from(inet).is_err());
+}
+
+#[test]
+fn accept_returns_unnamed_peer() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-addr-accept.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let _client = UnixStream::connect(&path).unwrap();
+    let (_server, addr) = listener.accept().unwrap();
+    assert!(addr.is_unnamed());
+    assert_eq!(addr.as_pathname(), None);
+    assert_eq!(format!("{addr:?}"), "(unnamed)");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn local_and_peer_addr() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-addr-local-peer.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    assert_eq!(
+        listener.local_addr().unwrap().as_pathname(),
+        Some(path.as_path())
+    );
+
+    let client = UnixStream::connect(&path).unwrap();
+    assert_eq!(
+        client.peer_addr().unwrap().as_pathname(),
+        Some(path.as_path())
+    );
+    let (_server, _) = listener.accept().unwrap();
+
+    let _ = std::fs::remove_file(&path);
+}
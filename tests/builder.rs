@@ -0,0 +1,160 @@
+use std::io::{Read, Write};
+use win_uds::net::{UnixListener, UnixListenerBuilder, UnixStream};
+
+#[test]
+fn builder_backlog() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-backlog.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::builder().backlog(1).bind(&path).unwrap();
+    let mut client = UnixStream::connect(&path).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    client.write_all(b"hi").unwrap();
+    let mut buf = [0u8; 2];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hi");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn builder_nonblocking() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-nonblocking.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListenerBuilder::new()
+        .nonblocking(true)
+        .bind(&path)
+        .unwrap();
+    let err = listener.accept().err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn builder_remove_stale() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-stale.sock");
+    let _ = std::fs::remove_file(&path);
+
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    assert!(UnixListener::bind(&path).is_err());
+    let _listener = UnixListenerBuilder::new()
+        .remove_stale(true)
+        .bind(&path)
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn builder_remove_stale_keeps_live_server() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-stale-live.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let err = UnixListenerBuilder::new()
+        .remove_stale(true)
+        .bind(&path)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    // The live server is untouched.
+    let _client = UnixStream::connect(&path).unwrap();
+    listener.accept().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn builder_remove_stale_keeps_regular_files() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-regular-file.sock");
+    std::fs::write(&path, b"not a socket").unwrap();
+
+    assert!(
+        UnixListenerBuilder::new()
+            .remove_stale(true)
+            .bind(&path)
+            .is_err()
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn builder_unlink_on_drop() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-unlink.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListenerBuilder::new()
+        .unlink_on_drop(true)
+        .bind(&path)
+        .unwrap();
+    let clone = listener.try_clone().unwrap();
+    drop(clone);
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    drop(listener);
+    assert!(std::fs::symlink_metadata(&path).is_err());
+}
+
+#[test]
+fn unlink_on_drop_waits_for_clones() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-unlink-clones.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListenerBuilder::new()
+        .unlink_on_drop(true)
+        .bind(&path)
+        .unwrap();
+    let clone = listener.try_clone().unwrap();
+    drop(listener);
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    // The clone is still reachable through the path.
+    let _client = UnixStream::connect(&path).unwrap();
+    clone.accept().unwrap();
+    drop(clone);
+    assert!(std::fs::symlink_metadata(&path).is_err());
+}
+
+#[test]
+fn unlink_on_drop_keeps_replaced_file() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-unlink-replaced.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let old = UnixListener::bind(&path).unwrap();
+    old.set_unlink_on_drop(true);
+    std::fs::remove_file(&path).unwrap();
+    let new = UnixListener::bind(&path).unwrap();
+    drop(old);
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    UnixStream::connect(&path).unwrap();
+    new.set_unlink_on_drop(true);
+    drop(new);
+    assert!(std::fs::symlink_metadata(&path).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn builder_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-builder-permissions.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = UnixListenerBuilder::new()
+        .permissions(std::fs::Permissions::from_mode(0o600))
+        .unlink_on_drop(true)
+        .bind(&path)
+        .unwrap();
+    let mode = std::fs::symlink_metadata(&path)
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
@@ -0,0 +1,93 @@
+use socket2::Socket;
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::unix::io::{FromRawFd, IntoRawFd, OwnedFd as Owned};
+#[cfg(windows)]
+use std::os::windows::io::{FromRawSocket, IntoRawSocket, OwnedSocket as Owned};
+use win_uds::net::{UnixListener, UnixStream};
+
+fn round_trip(mut a: UnixStream, mut b: UnixStream) {
+    a.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+}
+
+#[test]
+fn stream_owned() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let a = UnixStream::from(Owned::from(a));
+    round_trip(a, b);
+}
+
+#[test]
+fn stream_raw() {
+    let (a, b) = UnixStream::pair().unwrap();
+    #[cfg(unix)]
+    // SAFETY: the descriptor was just released by `a`.
+    let a = unsafe { UnixStream::from_raw_fd(a.into_raw_fd()) };
+    #[cfg(windows)]
+    // SAFETY: the socket was just released by `a`.
+    let a = unsafe { UnixStream::from_raw_socket(a.into_raw_socket()) };
+    round_trip(a, b);
+}
+
+#[test]
+fn stream_socket2() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let a = UnixStream::from(Socket::from(a));
+    round_trip(a, b);
+}
+
+#[test]
+fn listener_socket2() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-conversions-listener-socket2.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::from(Socket::from(UnixListener::bind(&path).unwrap()));
+    let client = UnixStream::connect(&path).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    round_trip(client, server);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn listener_owned() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-conversions-listener.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::builder()
+        .unlink_on_drop(true)
+        .bind(&path)
+        .unwrap();
+    // Handing the socket over leaves the file for its new owner.
+    let listener = UnixListener::from(Owned::from(listener));
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    let client = UnixStream::connect(&path).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    round_trip(client, server);
+    drop(listener);
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn listener_raw() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-conversions-listener-raw.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    #[cfg(unix)]
+    // SAFETY: the descriptor was just released by `listener`.
+    let listener = unsafe { UnixListener::from_raw_fd(listener.into_raw_fd()) };
+    #[cfg(windows)]
+    // SAFETY: the socket was just released by `listener`.
+    let listener = unsafe { UnixListener::from_raw_socket(listener.into_raw_socket()) };
+    let client = UnixStream::connect(&path).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    round_trip(client, server);
+    let _ = std::fs::remove_file(&path);
+}
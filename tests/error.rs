@@ -0,0 +1,139 @@
+use std::io;
+use win_uds::Error;
+use win_uds::net::{UnixListener, UnixStream};
+
+#[test]
+fn path_contains_nul() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error\0nul.sock");
+
+    let err = UnixListener::bind(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(
+        Error::classify(&err, &path),
+        Some(Error::PathContainsNul { path: path.clone() })
+    );
+    let err = UnixStream::connect(&path).err().unwrap();
+    assert_eq!(
+        Error::classify(&err, "ignored"),
+        Some(Error::PathContainsNul { path })
+    );
+}
+
+#[test]
+fn path_too_long() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("o".repeat(256));
+
+    let err = UnixListener::bind(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(
+        Error::classify(&err, &path),
+        Some(Error::PathTooLong { path })
+    );
+}
+
+#[test]
+fn server_not_running() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error-not-running.sock");
+    let _ = std::fs::remove_file(&path);
+
+    // Nothing at the path.
+    let err = UnixStream::connect(&path).err().unwrap();
+    assert_eq!(
+        Error::classify(&err, &path),
+        Some(Error::ServerNotRunning { path: path.clone() })
+    );
+
+    // A socket file left behind by a server that is gone.
+    drop(UnixListener::bind(&path).unwrap());
+    let err = UnixStream::connect(&path).err().unwrap();
+    assert_eq!(
+        Error::classify(&err, &path),
+        Some(Error::ServerNotRunning { path: path.clone() })
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn addr_in_use() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error-in-use.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = UnixListener::bind(&path).unwrap();
+    let err = UnixListener::bind(&path).err().unwrap();
+    assert_eq!(
+        Error::classify(&err, &path),
+        Some(Error::AddrInUse { path: path.clone() })
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn into_io_error() {
+    let error = Error::ServerNotRunning {
+        path: "/run/app.sock".into(),
+    };
+    assert_eq!(error.path(), std::path::Path::new("/run/app.sock"));
+    let err = io::Error::from(error.clone());
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    assert!(err.to_string().contains("/run/app.sock"));
+    assert_eq!(Error::classify(&err, "other"), Some(error));
+    assert_eq!(
+        Error::classify(&io::Error::from(io::ErrorKind::TimedOut), "/run/app.sock"),
+        None
+    );
+}
+
+#[test]
+fn errors_name_the_path() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error-context.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let err = UnixStream::connect(&path).err().unwrap();
+    assert!(matches!(
+        err.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+    ));
+    let message = err.to_string();
+    assert!(message.contains("connect"), "{message}");
+    assert!(message.contains(&*path.to_string_lossy()), "{message}");
+    // The recorded path wins over the one passed in.
+    assert_eq!(
+        Error::classify(&err, "elsewhere"),
+        Some(Error::ServerNotRunning { path: path.clone() })
+    );
+
+    let _listener = UnixListener::bind(&path).unwrap();
+    let err = UnixListener::bind(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    let message = err.to_string();
+    assert!(message.contains("bind"), "{message}");
+    assert!(message.contains(&*path.to_string_lossy()), "{message}");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(unix)]
+#[test]
+fn bind_missing_directory() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error-no-such-dir").join("x.sock");
+
+    let err = UnixListener::bind(&path).err().unwrap();
+    assert_eq!(Error::classify(&err, &path), None);
+}
+
+#[test]
+fn raw_os_error_through_context() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-error-raw-os-error.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let err = UnixStream::connect(&path).err().unwrap();
+    assert_eq!(err.raw_os_error(), None);
+    let code = Error::raw_os_error(&err).unwrap();
+    assert_eq!(io::Error::from_raw_os_error(code).kind(), err.kind());
+}
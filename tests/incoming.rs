@@ -0,0 +1,71 @@
+use std::io::{Read, Write};
+use std::time::Duration;
+use win_uds::net::{TransientErrorPolicy, UnixListener, UnixStream};
+
+#[test]
+fn incoming() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-incoming.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let path_clone = path.clone();
+    let cli = std::thread::spawn(move || {
+        for i in 0..3u8 {
+            let mut stream = UnixStream::connect(&path_clone).unwrap();
+            stream.write_all(&[i]).unwrap();
+        }
+    });
+
+    for (i, stream) in listener.incoming().take(3).enumerate() {
+        let mut buf = [0u8; 1];
+        stream.unwrap().read_exact(&mut buf).unwrap();
+        assert_eq!(buf[0], i as u8);
+    }
+    cli.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn into_incoming() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-into-incoming.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let srv = std::thread::spawn(move || {
+        let incoming = listener
+            .into_incoming()
+            .on_transient_error(TransientErrorPolicy::Backoff(Duration::from_millis(10)));
+        for stream in incoming.take(2) {
+            stream.unwrap().write_all(b"hi").unwrap();
+        }
+    });
+
+    for _ in 0..2 {
+        let mut stream = UnixStream::connect(&path).unwrap();
+        let mut buf = [0u8; 2];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hi");
+    }
+    srv.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn incoming_yields_non_transient_errors() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-incoming-nonblocking.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let mut incoming = listener
+        .incoming()
+        .on_transient_error(TransientErrorPolicy::Retry);
+    let Some(Err(err)) = incoming.next() else {
+        panic!("expected an error from a non-blocking listener");
+    };
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,294 @@
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use win_uds::net::{LocalListener, LocalStream};
+
+#[test]
+fn tcp_fallback_round_trip() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-tcp.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    assert!(listener.is_tcp());
+    let server = std::thread::spawn(move || {
+        let mut stream = listener.accept().unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+    });
+
+    let mut client = LocalStream::connect(&path).unwrap();
+    assert!(client.is_tcp());
+    client.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+    server.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_rejects_wrong_token() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-token.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let port: u16 = contents.lines().nth(1).unwrap().parse().unwrap();
+
+    // Go through the handshake without knowing the token.
+    let intruder = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream.write_all(&[0u8; 32]).unwrap();
+        let mut challenge = [0u8; 64];
+        stream.read_exact(&mut challenge).unwrap();
+        stream.write_all(&[0u8; 32]).unwrap();
+        let mut buf = [0u8; 1];
+        stream.read(&mut buf).unwrap_or(0)
+    });
+    assert_eq!(intruder.join().unwrap(), 0);
+
+    // The intruder never shows up in `accept`, but the next client does.
+    let client = LocalStream::connect(&path).unwrap();
+    let mut stream = listener.accept().unwrap();
+    drop(client);
+    let mut rest = Vec::new();
+    stream.read_to_end(&mut rest).unwrap();
+    assert!(rest.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_limits_pending_handshakes() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-limit.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let port: u16 = contents.lines().nth(1).unwrap().parse().unwrap();
+
+    // Fill every handshake slot with a client that never says anything.
+    let silent: Vec<_> = (0..128)
+        .map(|_| TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap())
+        .collect();
+    // One more is closed straight away instead of waiting for its handshake.
+    let mut extra = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+    extra.write_all(&[0u8; 32]).unwrap();
+    extra
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let mut buf = [0u8; 64];
+    match extra.read(&mut buf) {
+        Ok(n) => assert_eq!(n, 0),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::ConnectionReset),
+    }
+
+    // Once the stalled clients hang up, their slots free up again.
+    drop(silent);
+    let start = Instant::now();
+    let client = loop {
+        match LocalStream::connect(&path) {
+            Ok(client) => break client,
+            Err(_) if start.elapsed() < Duration::from_secs(2) => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => panic!("{e}"),
+        }
+    };
+    let _stream = listener.accept().unwrap();
+    drop(client);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_silent_client_does_not_block_accept() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-silent.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let port: u16 = contents.lines().nth(1).unwrap().parse().unwrap();
+
+    // Connects but never says anything.
+    let _silent = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+    let start = Instant::now();
+    let mut client = LocalStream::connect(&path).unwrap();
+    let mut stream = listener.accept().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_rejects_server_without_token() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-impostor.sock");
+    let _ = std::fs::remove_file(&path);
+
+    // A process that took over the port of a server that exited, leaving
+    // its file behind.
+    let impostor = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = impostor.local_addr().unwrap().port();
+    let token = "ab".repeat(32);
+    std::fs::write(&path, format!("win_uds-tcp-fallback\n{port}\n{token}\n")).unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = impostor.accept().unwrap();
+        let mut nonce = [0u8; 32];
+        stream.read_exact(&mut nonce).unwrap();
+        stream.write_all(&[0u8; 64]).unwrap();
+        // The client hangs up without sending anything else.
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        rest
+    });
+
+    let err = LocalStream::connect(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(server.join().unwrap().is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_removes_file_on_drop() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-drop.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    assert!(path.exists());
+    let err = LocalListener::bind_tcp(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    drop(listener);
+    assert!(!path.exists());
+}
+
+#[test]
+fn tcp_fallback_reclaims_stale_file() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-stale.sock");
+    let _ = std::fs::remove_file(&path);
+
+    // The file of a listener that crashed, naming a port nobody listens on.
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let token = "ab".repeat(32);
+    std::fs::write(&path, format!("win_uds-tcp-fallback\n{port}\n{token}\n")).unwrap();
+
+    let listener = LocalListener::bind_tcp(&path).unwrap();
+    let _client = LocalStream::connect(&path).unwrap();
+    let _stream = listener.accept().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_reclaims_file_of_port_taken_over() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-taken.sock");
+    let _ = std::fs::remove_file(&path);
+
+    // Another process took over the port and can't prove it knows the token.
+    let impostor = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = impostor.local_addr().unwrap().port();
+    let token = "ab".repeat(32);
+    std::fs::write(&path, format!("win_uds-tcp-fallback\n{port}\n{token}\n")).unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = impostor.accept().unwrap();
+        let mut nonce = [0u8; 32];
+        stream.read_exact(&mut nonce).unwrap();
+        stream.write_all(&[0u8; 64]).unwrap();
+    });
+
+    let _listener = LocalListener::bind_tcp(&path).unwrap();
+    server.join().unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_ne!(contents.lines().nth(1).unwrap(), port.to_string());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tcp_fallback_keeps_other_files() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-keep.sock");
+    std::fs::write(&path, "not a socket\n").unwrap();
+
+    let err = LocalListener::bind_tcp(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket\n");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(unix)]
+#[test]
+fn tcp_fallback_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-private.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = LocalListener::bind_tcp(&path).unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn connect_rejects_other_files() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-other.sock");
+    std::fs::write(&path, "not a socket\n").unwrap();
+
+    let err = LocalStream::connect(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn bind_prefers_unix_sockets() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-unix.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = LocalListener::bind(&path).unwrap();
+    assert_eq!(listener.is_tcp(), !win_uds::is_supported());
+    let mut client = LocalStream::connect(&path).unwrap();
+    let mut server = listener.accept().unwrap();
+    assert_eq!(client.is_tcp(), listener.is_tcp());
+    server.write_all(b"hi").unwrap();
+    let mut buf = [0u8; 2];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hi");
+    drop(listener);
+    assert!(!path.exists());
+    // The path can be bound again right away.
+    let _listener = LocalListener::bind(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn bind_reclaims_stale_socket() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-local-stale-unix.sock");
+    let _ = std::fs::remove_file(&path);
+
+    if !win_uds::is_supported() {
+        return;
+    }
+    // A listener that doesn't unlink its file, like one that crashed.
+    drop(win_uds::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let listener = LocalListener::bind(&path).unwrap();
+    assert!(!listener.is_tcp());
+    drop(listener);
+    assert!(!path.exists());
+}
@@ -0,0 +1,67 @@
+use std::io::{self, Read, Write};
+use std::time::Duration;
+use win_uds::net::{UnixListener, UnixStream};
+
+#[test]
+fn connect_nonblocking() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-connect-nonblocking.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let mut client = UnixStream::connect_nonblocking(&path).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    while !client.finish_connect().unwrap() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(client.take_error().unwrap().is_none());
+
+    server.write_all(b"hi").unwrap();
+    let mut buf = [0u8; 2];
+    let n = loop {
+        match client.read(&mut buf) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            res => break res.unwrap(),
+        }
+    };
+    assert_eq!(&buf[..n], &b"hi"[..n]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn connect_nonblocking_no_server() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-connect-nonblocking-none.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let err = UnixStream::connect_nonblocking(&path)
+        .and_then(|s| s.finish_connect())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+    ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn connect_nonblocking_backlog_full() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-connect-nonblocking-full.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = UnixListener::builder().backlog(0).bind(&path).unwrap();
+    let mut queued = Vec::new();
+    let err = loop {
+        match UnixStream::connect_nonblocking(&path) {
+            Ok(stream) => queued.push(stream),
+            Err(e) => break e,
+        }
+    };
+    assert!(!queued.is_empty());
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    let _ = std::fs::remove_file(&path);
+}
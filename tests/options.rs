@@ -0,0 +1,45 @@
+use std::io::ErrorKind;
+use std::time::Duration;
+use win_uds::net::UnixStream;
+
+#[test]
+fn timeouts() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    assert_eq!(a.read_timeout().unwrap(), None);
+    a.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(a.read_timeout().unwrap(), Some(Duration::from_secs(2)));
+    a.set_write_timeout(Some(Duration::from_secs(3))).unwrap();
+    assert_eq!(a.write_timeout().unwrap(), Some(Duration::from_secs(3)));
+    a.set_read_timeout(None).unwrap();
+    assert_eq!(a.read_timeout().unwrap(), None);
+
+    let err = a.set_read_timeout(Some(Duration::ZERO)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = a.set_write_timeout(Some(Duration::ZERO)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn buffer_sizes() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    a.set_send_buffer_size(64 * 1024).unwrap();
+    assert!(a.send_buffer_size().unwrap() > 0);
+    a.set_recv_buffer_size(64 * 1024).unwrap();
+    assert!(a.recv_buffer_size().unwrap() > 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn linger() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    a.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(a.linger().unwrap(), Some(Duration::from_secs(1)));
+    a.set_linger(None).unwrap();
+    assert_eq!(a.linger().unwrap(), None);
+}
+
+#[test]
+fn take_error() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    assert!(a.take_error().unwrap().is_none());
+}
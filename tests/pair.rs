@@ -0,0 +1,56 @@
+use std::io::{Read, Write};
+use win_uds::net::UnixStream;
+
+#[test]
+fn pair() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    a.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+
+    b.write_all(b"pong").unwrap();
+    a.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"pong");
+}
+
+#[test]
+fn pair_eof_on_drop() {
+    let (a, mut b) = UnixStream::pair().unwrap();
+    drop(a);
+    let mut buf = [0u8; 4];
+    assert_eq!(b.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn shared_reference_io() {
+    let (a, b) = UnixStream::pair().unwrap();
+    std::thread::scope(|scope| {
+        // One thread writes through `&a` while this one reads through `&a`.
+        scope.spawn(|| (&a).write_all(b"ping").unwrap());
+        let mut buf = [0u8; 4];
+        (&b).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+        (&b).write_all(b"pong").unwrap();
+        (&a).read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"pong");
+    });
+}
+
+#[test]
+fn shutdown_write() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    a.write_all(b"request").unwrap();
+    a.shutdown(std::net::Shutdown::Write).unwrap();
+
+    let mut request = Vec::new();
+    b.read_to_end(&mut request).unwrap();
+    assert_eq!(request, b"request");
+
+    // The other direction is still open.
+    b.write_all(b"response").unwrap();
+    drop(b);
+    let mut response = Vec::new();
+    a.read_to_end(&mut response).unwrap();
+    assert_eq!(response, b"response");
+}
@@ -0,0 +1,42 @@
+use std::io::{Read, Write};
+use win_uds::net::{MsgFlags, UnixStream};
+
+#[test]
+fn peek() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    a.write_all(b"v2 hello").unwrap();
+
+    let mut buf = [0u8; 2];
+    assert_eq!(b.peek(&mut buf).unwrap(), 2);
+    assert_eq!(&buf, b"v2");
+
+    // Peeking doesn't consume anything.
+    let mut all = [0u8; 8];
+    b.read_exact(&mut all).unwrap();
+    assert_eq!(&all, b"v2 hello");
+}
+
+#[test]
+fn recv_wait_all() {
+    let (a, b) = UnixStream::pair().unwrap();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for chunk in [&b"he"[..], b"ll", b"o!"] {
+                (&a).write_all(chunk).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+        });
+        let mut buf = [0u8; 6];
+        assert_eq!(b.recv_with_flags(&mut buf, MsgFlags::WAIT_ALL).unwrap(), 6);
+        assert_eq!(&buf, b"hello!");
+    });
+}
+
+#[test]
+fn msg_flags() {
+    let flags = MsgFlags::PEEK | MsgFlags::WAIT_ALL;
+    assert!(flags.contains(MsgFlags::PEEK));
+    assert!(flags.contains(MsgFlags::WAIT_ALL));
+    assert!(!MsgFlags::NONE.contains(MsgFlags::PEEK));
+    assert_eq!(MsgFlags::default(), MsgFlags::NONE);
+}
@@ -0,0 +1,54 @@
+use std::io::ErrorKind;
+use win_uds::net::{UnixListener, UnixStream};
+
+#[test]
+fn bind_reclaim_stale_socket() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-reclaim-stale.sock");
+    let _ = std::fs::remove_file(&path);
+
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    let listener = UnixListener::bind_reclaim(&path).unwrap();
+    let _client = UnixStream::connect(&path).unwrap();
+    listener.accept().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn bind_reclaim_live_server() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-reclaim-live.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let err = UnixListener::bind_reclaim(&path).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::AddrInUse);
+    assert!(err.to_string().contains("live server"));
+    // The live server is untouched.
+    let _client = UnixStream::connect(&path).unwrap();
+    listener.accept().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn bind_reclaim_missing_file() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-reclaim-missing.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = UnixListener::bind_reclaim(&path).unwrap();
+    assert!(std::fs::symlink_metadata(&path).is_ok());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn bind_reclaim_keeps_regular_files() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-reclaim-regular-file.sock");
+    std::fs::write(&path, b"not a socket").unwrap();
+
+    assert!(UnixListener::bind_reclaim(&path).is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+    let _ = std::fs::remove_file(&path);
+}
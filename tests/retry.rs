@@ -0,0 +1,50 @@
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+use win_uds::net::{RetryPolicy, UnixListener, UnixStream};
+
+#[test]
+fn connect_with_retry_waits_for_server() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-retry.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let path_clone = path.clone();
+    let srv = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(300));
+        let listener = UnixListener::bind(&path_clone).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.write_all(b"up").unwrap();
+    });
+
+    let policy = RetryPolicy::new(Duration::from_secs(10)).max_delay(Duration::from_millis(50));
+    let mut stream = UnixStream::connect_with_retry(&path, policy).unwrap();
+    let mut buf = [0u8; 2];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"up");
+
+    srv.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn connect_with_retry_gives_up() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-retry-gives-up.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let start = Instant::now();
+    let policy = RetryPolicy::new(Duration::from_millis(200)).jitter(false);
+    let err = UnixStream::connect_with_retry(&path, policy).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn connect_with_retry_does_not_retry_invalid_paths() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("oo\0oo");
+    let err = UnixStream::connect_with_retry(&path, RetryPolicy::default())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,7 @@
+#[test]
+fn is_supported() {
+    // Every platform the tests run on has AF_UNIX, and the answer is cached.
+    assert!(win_uds::is_supported());
+    assert!(win_uds::is_supported());
+    assert!(win_uds::net::is_supported());
+}
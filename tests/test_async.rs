@@ -2,8 +2,8 @@
 
 use std::io;
 
-use futures::{AsyncReadExt, AsyncWriteExt};
-use win_uds::net::{AsyncListener, AsyncStream};
+use futures::{AsyncReadExt, AsyncWriteExt, StreamExt};
+use win_uds::net::{AsyncListener, AsyncStream, MsgFlags};
 
 #[tokio::test]
 async fn async_echo() -> io::Result<()> {
@@ -218,3 +218,367 @@ async fn async_concurrent_accept() -> io::Result<()> {
     let _ = std::fs::remove_file(&path);
     Ok(())
 }
+
+#[tokio::test]
+async fn async_local_and_peer_addr() -> io::Result<()> {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_addr.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = AsyncListener::bind(&path)?;
+    assert_eq!(listener.local_addr()?.as_pathname(), Some(path.as_path()));
+
+    let client = AsyncStream::connect(&path).await?;
+    assert_eq!(client.peer_addr()?.as_pathname(), Some(path.as_path()));
+    let (server, addr) = listener.accept().await?;
+    assert!(addr.is_unnamed());
+    assert!(server.peer_addr()?.is_unnamed());
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_pair() -> io::Result<()> {
+    let (mut a, mut b) = AsyncStream::pair()?;
+    a.write_all(b"ping").await?;
+    let mut buf = [0u8; 4];
+    b.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"ping");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_shared_reference_io() -> io::Result<()> {
+    let (a, b) = AsyncStream::pair()?;
+    // Read and write the same stream concurrently through shared references.
+    let (mut reader, mut writer) = (&a, &a);
+    let mut peer = &b;
+    let mut buf = [0u8; 4];
+    let (echoed, sent) = futures::join!(
+        async {
+            reader.read_exact(&mut buf).await?;
+            writer.write_all(&buf).await
+        },
+        async {
+            peer.write_all(b"ping").await?;
+            let mut buf = [0u8; 4];
+            peer.read_exact(&mut buf).await?;
+            Ok::<_, io::Error>(buf)
+        }
+    );
+    echoed?;
+    assert_eq!(&sent?, b"ping");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_peek() -> io::Result<()> {
+    let (mut a, mut b) = AsyncStream::pair()?;
+    let mut buf = [0u8; 2];
+    let (peeked, written) = futures::join!(b.peek(&mut buf), a.write_all(b"v1"));
+    written?;
+    assert_eq!(peeked?, 2);
+    assert_eq!(&buf, b"v1");
+
+    let mut all = [0u8; 2];
+    b.read_exact(&mut all).await?;
+    assert_eq!(&all, b"v1");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_recv_wait_all_returns_short_read() -> io::Result<()> {
+    let (mut a, b) = AsyncStream::pair()?;
+    a.write_all(b"v1").await?;
+    let mut buf = [0u8; 8];
+    let n = b.recv_with_flags(&mut buf, MsgFlags::WAIT_ALL).await?;
+    assert_eq!(&buf[..n], b"v1");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_shutdown_write() -> io::Result<()> {
+    let (mut a, mut b) = AsyncStream::pair()?;
+    a.write_all(b"request").await?;
+    a.shutdown_write().await?;
+
+    let mut request = Vec::new();
+    b.read_to_end(&mut request).await?;
+    assert_eq!(request, b"request");
+
+    b.write_all(b"response").await?;
+    b.shutdown(std::net::Shutdown::Both)?;
+    let mut response = Vec::new();
+    a.read_to_end(&mut response).await?;
+    assert_eq!(response, b"response");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_from_std_into_std() -> io::Result<()> {
+    use win_uds::net::{UnixListener, UnixStream};
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_from_std.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = AsyncListener::from_std(UnixListener::bind(&path)?)?;
+    let mut client = AsyncStream::from_std(UnixStream::connect(&path)?)?;
+    let (server, _addr) = listener.accept().await?;
+
+    client.write_all(b"hi").await?;
+    let mut server = server.into_std()?;
+    server.set_nonblocking(false)?;
+    let mut buf = [0u8; 2];
+    std::io::Read::read_exact(&mut server, &mut buf)?;
+    assert_eq!(&buf, b"hi");
+
+    let listener = listener.into_std()?;
+    assert_eq!(listener.local_addr()?.as_pathname(), Some(path.as_path()));
+    drop(listener);
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_vectored_io() -> io::Result<()> {
+    use std::io::{IoSlice, IoSliceMut};
+
+    let (mut a, mut b) = AsyncStream::pair()?;
+    let n = a
+        .write_vectored(&[IoSlice::new(b"head"), IoSlice::new(b"body")])
+        .await?;
+    assert_eq!(n, 8);
+
+    let mut head = [0u8; 4];
+    let mut body = [0u8; 4];
+    let n = b
+        .read_vectored(&mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)])
+        .await?;
+    assert_eq!(n, 8);
+    assert_eq!(&head, b"head");
+    assert_eq!(&body, b"body");
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_incoming() -> io::Result<()> {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_incoming.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = AsyncListener::bind(&path)?;
+
+    let path_clone = path.clone();
+    let client_handle = tokio::spawn(async move {
+        for i in 0..3u8 {
+            let mut client = AsyncStream::connect(&path_clone).await.unwrap();
+            client.write_all(&[i]).await.unwrap();
+        }
+    });
+
+    listener
+        .incoming()
+        .take(3)
+        .for_each_concurrent(None, |stream| async move {
+            let mut buf = [0u8; 1];
+            stream.unwrap().read_exact(&mut buf).await.unwrap();
+            assert!(buf[0] < 3);
+        })
+        .await;
+
+    client_handle.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_poll_accept() -> io::Result<()> {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_poll_accept.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = AsyncListener::bind(&path)?;
+    let accept = std::future::poll_fn(|cx| listener.poll_accept(cx));
+    let (accepted, connected) = tokio::join!(accept, AsyncStream::connect(&path));
+    let (mut server, _addr) = accepted?;
+    let mut client = connected?;
+
+    client.write_all(b"hi").await?;
+    let mut buf = [0u8; 2];
+    server.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"hi");
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn async_connect_backlog_full() -> io::Result<()> {
+    use std::time::Duration;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_backlog_full.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = win_uds::net::UnixListenerBuilder::new()
+        .backlog(0)
+        .bind_async(&path)?;
+
+    // Fill the backlog until connecting stops making progress.
+    let mut queued = Vec::new();
+    while let Ok(stream) =
+        tokio::time::timeout(Duration::from_millis(100), AsyncStream::connect(&path)).await
+    {
+        queued.push(stream?);
+    }
+    assert!(!queued.is_empty());
+
+    // The next connect waits for room without stalling the executor.
+    let pending = tokio::spawn(AsyncStream::connect(path.clone()));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!pending.is_finished());
+
+    let _accepted = listener.accept().await?;
+    pending.await.unwrap()?;
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_connect_timeout_zero() -> io::Result<()> {
+    use std::time::Duration;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_connect_timeout_zero.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = AsyncListener::bind(&path)?;
+    let err = AsyncStream::connect_timeout(&path, Duration::ZERO)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn async_connect_time_out() -> io::Result<()> {
+    use std::time::Duration;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_connect_time_out.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = win_uds::net::UnixListenerBuilder::new()
+        .backlog(0)
+        .bind_async(&path)?;
+    let mut queued = Vec::new();
+    let err = loop {
+        match AsyncStream::connect_timeout(&path, Duration::from_millis(200)).await {
+            Ok(stream) => queued.push(stream),
+            Err(e) => break e,
+        }
+    };
+    assert!(!queued.is_empty());
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_connect_with_retry() -> io::Result<()> {
+    use std::time::Duration;
+    use win_uds::net::RetryPolicy;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_retry.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let path_clone = path.clone();
+    let srv = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = AsyncListener::bind(&path_clone).unwrap();
+        let (mut s, _addr) = listener.accept().await.unwrap();
+        s.write_all(b"up").await.unwrap();
+    });
+
+    let policy = RetryPolicy::new(Duration::from_secs(10));
+    let mut client = AsyncStream::connect_with_retry(&path, policy).await?;
+    let mut buf = [0u8; 2];
+    client.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"up");
+
+    srv.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_builder() -> io::Result<()> {
+    use win_uds::net::UnixListenerBuilder;
+
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_builder.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListenerBuilder::new()
+        .backlog(8)
+        .unlink_on_drop(true)
+        .bind_async(&path)?;
+    let mut client = AsyncStream::connect(&path).await?;
+    let (mut server, _addr) = listener.accept().await?;
+    client.write_all(b"hi").await?;
+    let mut buf = [0u8; 2];
+    server.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"hi");
+
+    drop(listener);
+    assert!(std::fs::symlink_metadata(&path).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_bind_reclaim() -> io::Result<()> {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_bind_reclaim.sock");
+    let _ = std::fs::remove_file(&path);
+
+    drop(AsyncListener::bind(&path)?);
+    let listener = AsyncListener::bind_reclaim(&path).await?;
+    let err = AsyncListener::bind_reclaim(&path).await.err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+    let _client = AsyncStream::connect(&path).await?;
+    listener.accept().await?;
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_errors_name_the_path() -> io::Result<()> {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test_async_error_context.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let err = AsyncStream::connect(&path).await.err().unwrap();
+    assert!(matches!(
+        err.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+    ));
+    assert!(err.to_string().contains(&*path.to_string_lossy()));
+
+    let _listener = AsyncListener::bind(&path)?;
+    let err = AsyncListener::bind(&path).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    assert!(err.to_string().contains(&*path.to_string_lossy()));
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
@@ -65,3 +65,84 @@ fn write_time_out() {
     srv.join().unwrap();
     let _ = std::fs::remove_file(&path);
 }
+
+#[test]
+fn connect_timeout() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("connect_timeout.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let mut stream = UnixStream::connect_timeout(&path, Duration::from_secs(1)).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    stream.write_all(b"ok").unwrap();
+    let mut buf = [0u8; 2];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn connect_timeout_no_server() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("connect_timeout_no_server.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let err = UnixStream::connect_timeout(&path, Duration::from_secs(1))
+        .err()
+        .unwrap();
+    assert_ne!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn connect_timeout_zero() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("connect_timeout_zero.sock");
+    let err = UnixStream::connect_timeout(&path, Duration::ZERO)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn connect_time_out() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("connect_time_out.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let _listener = UnixListener::builder().backlog(0).bind(&path).unwrap();
+    let mut queued = Vec::new();
+    let err = loop {
+        match UnixStream::connect_timeout(&path, Duration::from_millis(200)) {
+            Ok(stream) => queued.push(stream),
+            Err(e) => break e,
+        }
+    };
+    assert!(!queued.is_empty());
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn read_timeout_set_on_clone() {
+    let (mut a, _b) = UnixStream::pair().unwrap();
+    let clone = a.try_clone().unwrap();
+    clone
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    let mut buf = [0u8; 1];
+    let err = a.read(&mut buf).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn read_timeout_set_through_socket2() {
+    let (mut a, _b) = UnixStream::pair().unwrap();
+    socket2::SockRef::from(&a)
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    let mut buf = [0u8; 1];
+    let err = a.read(&mut buf).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+}
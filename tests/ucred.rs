@@ -0,0 +1,33 @@
+use win_uds::net::{UnixListener, UnixStream};
+
+#[test]
+fn peer_cred_pair() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    let cred = a.peer_cred().unwrap();
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        windows
+    ))]
+    assert_eq!(cred.pid, Some(std::process::id()));
+    #[cfg(unix)]
+    assert!(cred.uid.is_some() && cred.gid.is_some());
+    #[cfg(windows)]
+    assert_eq!((cred.uid, cred.gid), (None, None));
+}
+
+#[test]
+fn peer_cred_accepted() {
+    let tmp = std::env::temp_dir();
+    let path = tmp.join("test-uds-peer-cred.sock");
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let client = UnixStream::connect(&path).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    assert_eq!(server.peer_cred().unwrap(), client.peer_cred().unwrap());
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,21 @@
+use std::io::{IoSlice, IoSliceMut, Read, Write};
+use win_uds::net::UnixStream;
+
+#[test]
+fn vectored_io() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+
+    let n = a
+        .write_vectored(&[IoSlice::new(b"head"), IoSlice::new(b"body")])
+        .unwrap();
+    assert_eq!(n, 8);
+
+    let mut head = [0u8; 4];
+    let mut body = [0u8; 4];
+    let n = b
+        .read_vectored(&mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)])
+        .unwrap();
+    assert_eq!(n, 8);
+    assert_eq!(&head, b"head");
+    assert_eq!(&body, b"body");
+}